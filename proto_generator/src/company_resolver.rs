@@ -0,0 +1,193 @@
+//! Groups notices by the company that filed them. The same employer is often
+//! written differently from one week to the next ("Acme Inc", "ACME, Inc.",
+//! "Acme Incorporated d/b/a Acme Labs"), so names are normalised before being
+//! compared, and names too different to normalise alike can be tied together
+//! with aliases.
+use std::collections::HashMap;
+
+use crate::{
+    notice_id::NoticeId,
+    notices::{WARNNotice, WARNNotices},
+};
+
+/// Words introducing another name of the company. Only the name before them
+/// is compared, as it is the legal name.
+const OTHER_NAME_MARKERS: [&str; 7] = ["d/b/a", "dba", "f/k/a", "fka", "a/k/a", "aka", "t/a"];
+/// Phrases introducing another name of the company, spelt out
+const OTHER_NAME_PHRASES: [&str; 3] = ["doing business as", "formerly known as", "also known as"];
+/// Words ending a name that only tell its legal form
+const LEGAL_SUFFIXES: [&str; 16] = [
+    "inc",
+    "incorporated",
+    "llc",
+    "ltd",
+    "limited",
+    "corp",
+    "corporation",
+    "co",
+    "company",
+    "lp",
+    "llp",
+    "lllp",
+    "plc",
+    "pc",
+    "pllc",
+    "pa",
+];
+
+/// A company and the other names it files notices under
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompanyAlias {
+    /// The name the company is reported under
+    pub name: String,
+    pub aliases: Vec<String>,
+}
+
+/// Resolves the firm names of notices to companies
+#[derive(Clone, Debug, Default)]
+pub struct CompanyResolver {
+    /// Canonical name of each aliased company, by each of its normalised names
+    canonical_names: HashMap<String, String>,
+}
+
+impl CompanyResolver {
+    /// # Params
+    /// * companies - Companies whose names are too different to be told
+    ///   apart by normalising them alone
+    pub fn new(companies: &[CompanyAlias]) -> CompanyResolver {
+        let mut canonical_names = HashMap::new();
+        for company in companies {
+            for name in std::iter::once(&company.name).chain(company.aliases.iter()) {
+                canonical_names.insert(Self::normalise(name), company.name.trim().to_string());
+            }
+        }
+        CompanyResolver { canonical_names }
+    }
+
+    /// # Brief
+    /// Sets the company id and canonical name of every notice with a firm
+    /// name. Companies without an alias are named by the spelling most of
+    /// their notices use, the first seen on a tie.
+    pub fn resolve(&self, notices: &mut WARNNotices) {
+        let keys = notices
+            .notices
+            .iter()
+            .map(|notice| self.company_key(notice.firm_name()))
+            .collect::<Vec<Option<String>>>();
+
+        let mut spellings: HashMap<&str, Vec<(&str, usize)>> = HashMap::new();
+        for (notice, key) in notices.notices.iter().zip(keys.iter()) {
+            let Some(key) = key else { continue };
+            let spelling = notice.firm_name().trim();
+            let counts = spellings.entry(key).or_default();
+            match counts.iter_mut().find(|(seen, _)| *seen == spelling) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((spelling, 1)),
+            }
+        }
+        let names = spellings
+            .into_iter()
+            .map(|(key, counts)| {
+                let name = match self.canonical_names.get(key) {
+                    Some(canonical_name) => canonical_name.clone(),
+                    None => counts
+                        .iter()
+                        .rev()
+                        .max_by_key(|(_, count)| *count)
+                        .map(|(spelling, _)| spelling.to_string())
+                        .unwrap_or_default(),
+                };
+                (key.to_string(), name)
+            })
+            .collect::<HashMap<String, String>>();
+
+        for (notice, key) in notices.notices.iter_mut().zip(keys) {
+            if let Some(key) = key {
+                Self::set_company(notice, &key, &names[&key]);
+            }
+        }
+    }
+
+    /// # Return
+    /// The normalised name the company is told apart by, that of its
+    /// canonical name if the firm name is an alias. None if the firm name is
+    /// empty.
+    pub fn company_key(&self, firm_name: &str) -> Option<String> {
+        let normalised = Self::normalise(firm_name);
+        if normalised.is_empty() {
+            return None;
+        }
+        match self.canonical_names.get(&normalised) {
+            Some(canonical_name) => Some(Self::normalise(canonical_name)),
+            None => Some(normalised),
+        }
+    }
+
+    /// # Return
+    /// An id for the company, the same across scrapes as long as its
+    /// normalised name is
+    pub fn company_id(company_key: &str) -> String {
+        format!("{:016x}", NoticeId::fnv1a(company_key.as_bytes()))
+    }
+
+    /// # Brief
+    /// Lowercases the name, keeps only the part before any other name it is
+    /// given (i.e. "d/b/a"), drops punctuation, a leading "the" and the
+    /// legal form (i.e. "Inc.", "LLC") it ends with.
+    pub fn normalise(firm_name: &str) -> String {
+        let lowercase = firm_name.to_lowercase().replace('&', " and ");
+        let mut words = lowercase.split_whitespace().collect::<Vec<&str>>();
+        if let Some(other_name_idx) = Self::other_name_start(&words) {
+            words.truncate(other_name_idx);
+        }
+
+        // Dots and apostrophes are dropped so "L.L.C." reads "llc"
+        let mut words = words
+            .join(" ")
+            .chars()
+            .filter(|c| *c != '.' && *c != '\'')
+            .map(|c| match c.is_alphanumeric() {
+                true => c,
+                false => ' ',
+            })
+            .collect::<String>()
+            .split_whitespace()
+            .map(str::to_string)
+            .collect::<Vec<String>>();
+
+        if words.len() > 1 && words[0] == "the" {
+            words.remove(0);
+        }
+        while words.len() > 1
+            && words
+                .last()
+                .is_some_and(|word| LEGAL_SUFFIXES.contains(&word.as_str()))
+        {
+            words.pop();
+        }
+
+        words.join(" ")
+    }
+
+    /// Index of the first word introducing another name, if it isn't the
+    /// first word of the name
+    fn other_name_start(words: &[&str]) -> Option<usize> {
+        let bare = words
+            .iter()
+            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '/'))
+            .map(|word| word.replace('.', ""))
+            .collect::<Vec<String>>();
+
+        (1..bare.len()).find(|idx| {
+            OTHER_NAME_MARKERS.contains(&bare[*idx].as_str())
+                || OTHER_NAME_PHRASES
+                    .iter()
+                    .any(|phrase| bare[*idx..].join(" ").starts_with(&format!("{} ", phrase)))
+        })
+    }
+
+    fn set_company(notice: &mut WARNNotice, company_key: &str, company_name: &str) {
+        notice.set_company_id(Self::company_id(company_key));
+        notice.set_company_name(company_name.to_string());
+    }
+}
@@ -0,0 +1,96 @@
+//! A company's notices in the order they were filed, with running totals, to
+//! tell an employer laying off once from one laying off again and again.
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::{
+    company_resolver::CompanyResolver,
+    employee_counts::EmployeeCounts,
+    notice_dates::NoticeDates,
+    notices::{CompanyTimeline, CompanyTimelines, TimelineEntry, WARNNotice, WARNNotices},
+};
+
+pub struct CompanyTimelineBuilder {}
+
+impl CompanyTimelineBuilder {
+    /// # Brief
+    /// Groups the notices by the company they were resolved to, resolving
+    /// those that weren't without aliases, and orders each company's
+    /// notices by the date they were received. Notices without a received
+    /// date are placed by their effective date, and those with neither last.
+    /// # Return
+    /// A timeline per company, ordered by company name
+    pub fn build(notices: &WARNNotices) -> CompanyTimelines {
+        let (resolved, unresolved): (Vec<WARNNotice>, Vec<WARNNotice>) = notices
+            .notices
+            .iter()
+            .cloned()
+            .partition(WARNNotice::has_company_id);
+        let mut unresolved = WARNNotices {
+            notices: unresolved,
+            ..Default::default()
+        };
+        CompanyResolver::default().resolve(&mut unresolved);
+
+        let mut companies: BTreeMap<String, Vec<WARNNotice>> = BTreeMap::new();
+        for notice in resolved.into_iter().chain(unresolved.notices) {
+            // Notices without a firm name belong to no company
+            if notice.has_company_id() {
+                companies
+                    .entry(notice.company_id().to_string())
+                    .or_default()
+                    .push(notice);
+            }
+        }
+
+        let mut timelines = CompanyTimelines::new();
+        timelines.timelines = companies
+            .into_iter()
+            .map(|(company_id, company_notices)| Self::timeline(company_id, company_notices))
+            .collect();
+        timelines
+            .timelines
+            .sort_by(|left, right| left.company_name().cmp(right.company_name()));
+        timelines
+    }
+
+    fn timeline(company_id: String, mut notices: Vec<WARNNotice>) -> CompanyTimeline {
+        let company_name = notices
+            .first()
+            .map(|notice| notice.company_name().to_string())
+            .unwrap_or_default();
+        // Stable, so notices filed the same day keep the order they were found in
+        notices.sort_by_key(Self::filed_on);
+
+        let mut cumulative_notice_count = 0;
+        let mut cumulative_affected_employees = 0;
+        let entries = notices
+            .into_iter()
+            .map(|notice| {
+                cumulative_notice_count += 1;
+                cumulative_affected_employees +=
+                    EmployeeCounts::parse(notice.affected_employees()).unwrap_or(0);
+
+                let mut entry = TimelineEntry::new();
+                entry.notice = Some(notice).into();
+                entry.set_cumulative_notice_count(cumulative_notice_count);
+                entry.set_cumulative_affected_employees(cumulative_affected_employees);
+                entry
+            })
+            .collect();
+
+        let mut timeline = CompanyTimeline::new();
+        timeline.set_company_name(company_name);
+        timeline.set_company_id(company_id);
+        timeline.entries = entries;
+        timeline
+    }
+
+    /// Sort key placing notices without any date last
+    fn filed_on(notice: &WARNNotice) -> (bool, Option<NaiveDate>) {
+        let date = NoticeDates::parse(notice.date_received())
+            .or_else(|| NoticeDates::parse(notice.effective_date()));
+        (date.is_none(), date)
+    }
+}
@@ -0,0 +1,30 @@
+//! Utilities for interpreting the affected employee counts stored on notices.
+//! The yearly spreadsheet stores them as numbers (sometimes floats, i.e. 150.0)
+//! while the weekly page can contain text such as "Approximately 1,200".
+pub struct EmployeeCounts {}
+
+impl EmployeeCounts {
+    /// # Return
+    /// * The first whole number found in the string
+    /// * None if the string does not contain a number
+    pub fn parse(raw_count: &str) -> Option<u32> {
+        let digits = raw_count
+            .trim()
+            .chars()
+            .skip_while(|c| !c.is_ascii_digit())
+            .take_while(|c| c.is_ascii_digit() || *c == ',')
+            .filter(|c| *c != ',')
+            .collect::<String>();
+
+        digits.parse::<u32>().ok()
+    }
+
+    /// # Return
+    /// Sum of every count that could be understood
+    pub fn total<'a, I>(raw_counts: I) -> u32
+    where
+        I: Iterator<Item = &'a str>,
+    {
+        raw_counts.filter_map(Self::parse).sum()
+    }
+}
@@ -0,0 +1,267 @@
+//! Exports notices as an Atom feed (RFC 4287) with one entry per notice
+use std::{
+    collections::HashMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, SecondsFormat, Utc};
+
+use crate::{
+    exporters::{escape_xml, NoticeExporter},
+    notice_dates::NoticeDates,
+    notice_id::NoticeId,
+    notices::{WARNNotice, WARNNotices},
+};
+
+const ENTRY_ID_PREFIX: &str = "urn:warn-notice:";
+/// How long an entry is remembered after it last appeared in the feed. States
+/// publish notices a year at a time, so this outlives a notice's listing.
+const ENTRY_RETENTION_DAYS: i64 = 400;
+
+/// What the feed last knew about an entry
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeedEntryState {
+    pub fingerprint: String,
+    pub updated: DateTime<Utc>,
+    /// When the entry was last part of the feed
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Remembers the content of each entry between runs so an entry's `updated`
+/// timestamp only moves when one of its fields changes.
+/// Entries that have not been in the feed for [`ENTRY_RETENTION_DAYS`] are
+/// forgotten so the state does not grow without bound.
+/// Stored on disk as one tab separated `id fingerprint updated last_seen` line
+/// per entry.
+#[derive(Clone, Debug, Default)]
+pub struct FeedState {
+    entries: HashMap<String, FeedEntryState>,
+}
+
+impl FeedState {
+    /// Loads the state from disk. A missing file is treated as an empty state
+    /// so the first run does not need any setup.
+    pub fn load(path: &Path) -> io::Result<FeedState> {
+        if !path.exists() {
+            return Ok(FeedState::default());
+        }
+
+        let entries = fs::read_to_string(path)?
+            .lines()
+            .filter_map(|line| {
+                let mut columns = line.split('\t');
+                let id = columns.next()?;
+                let fingerprint = columns.next()?;
+                let updated = DateTime::parse_from_rfc3339(columns.next()?)
+                    .ok()?
+                    .with_timezone(&Utc);
+                // Older state files have no last seen column
+                let last_seen = match columns.next() {
+                    Some(last_seen) => DateTime::parse_from_rfc3339(last_seen)
+                        .ok()?
+                        .with_timezone(&Utc),
+                    None => updated,
+                };
+                Some((
+                    id.to_string(),
+                    FeedEntryState {
+                        fingerprint: fingerprint.to_string(),
+                        updated,
+                        last_seen,
+                    },
+                ))
+            })
+            .collect();
+
+        Ok(FeedState { entries })
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut ids = self.entries.keys().collect::<Vec<&String>>();
+        ids.sort();
+
+        let mut file = fs::File::create(path)?;
+        for id in ids {
+            let entry = &self.entries[id];
+            writeln!(
+                file,
+                "{}\t{}\t{}\t{}",
+                id,
+                entry.fingerprint,
+                entry.updated.to_rfc3339_opts(SecondsFormat::Secs, true),
+                entry.last_seen.to_rfc3339_opts(SecondsFormat::Secs, true)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Records the current content of every notice.
+    /// * New entries are stamped with the date the notice was received, or
+    ///   `now` if that is unknown
+    /// * Entries whose content changed are stamped with `now`
+    /// * Unchanged entries keep their previous timestamp
+    /// * Entries missing from the feed for longer than the retention period
+    ///   are dropped
+    pub fn refresh(&mut self, notices: &WARNNotices, now: DateTime<Utc>) {
+        let ids = NoticeId::unique_ids(&notices.notices);
+        for (id, notice) in ids.into_iter().zip(notices.notices.iter()) {
+            let fingerprint = NoticeId::content_fingerprint(notice);
+            let updated = match self.entries.get(&id) {
+                Some(previous) if previous.fingerprint == fingerprint => previous.updated,
+                Some(_) => now,
+                None => Self::received_timestamp(notice).unwrap_or(now),
+            };
+            self.entries.insert(
+                id,
+                FeedEntryState {
+                    fingerprint,
+                    updated,
+                    last_seen: now,
+                },
+            );
+        }
+
+        let retention = Duration::days(ENTRY_RETENTION_DAYS);
+        self.entries
+            .retain(|_, entry| now - entry.last_seen <= retention);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&FeedEntryState> {
+        self.entries.get(id)
+    }
+
+    fn received_timestamp(notice: &WARNNotice) -> Option<DateTime<Utc>> {
+        NoticeDates::parse(notice.date_received()).map(Self::start_of_day)
+    }
+
+    fn start_of_day(date: NaiveDate) -> DateTime<Utc> {
+        date.and_time(NaiveTime::MIN).and_utc()
+    }
+}
+
+/// Renders notices into an Atom feed
+pub struct AtomFeedExporter {
+    title: String,
+    feed_id: String,
+    generated_at: DateTime<Utc>,
+    state: FeedState,
+}
+
+impl AtomFeedExporter {
+    /// # Params
+    /// * title - Title of the feed shown by feed readers
+    /// * feed_id - Permanent, unique id of the feed (i.e. a url or urn)
+    /// * generated_at - When the feed was generated
+    /// * state - Entry state already refreshed with the notices to export
+    pub fn new(
+        title: String,
+        feed_id: String,
+        generated_at: DateTime<Utc>,
+        state: FeedState,
+    ) -> AtomFeedExporter {
+        AtomFeedExporter {
+            title,
+            feed_id,
+            generated_at,
+            state,
+        }
+    }
+
+    fn render_entry(&self, id: &str, notice: &WARNNotice) -> String {
+        let updated = self
+            .state
+            .get(id)
+            .map(|entry| entry.updated)
+            .unwrap_or(self.generated_at);
+
+        let mut entry = String::new();
+        entry.push_str("  <entry>\n");
+        entry.push_str(&format!(
+            "    <id>{}{}</id>\n",
+            ENTRY_ID_PREFIX,
+            escape_xml(id)
+        ));
+        entry.push_str(&format!(
+            "    <title>{}</title>\n",
+            escape_xml(&Self::entry_title(notice))
+        ));
+        entry.push_str(&format!(
+            "    <updated>{}</updated>\n",
+            Self::format_timestamp(updated)
+        ));
+        entry.push_str(&format!(
+            "    <content type=\"text\">{}</content>\n",
+            escape_xml(&Self::entry_content(notice))
+        ));
+        entry.push_str("  </entry>\n");
+        entry
+    }
+
+    fn entry_title(notice: &WARNNotice) -> String {
+        match notice.has_effective_date() {
+            true => format!(
+                "{} - effective {}",
+                notice.firm_name(),
+                notice.effective_date()
+            ),
+            false => notice.firm_name().to_string(),
+        }
+    }
+
+    /// One line per field present on the notice
+    fn entry_content(notice: &WARNNotice) -> String {
+        [
+            ("Company", notice.firm_name.as_ref()),
+            ("Location(s)", notice.firm_locations.as_ref()),
+            ("Affected employees", notice.affected_employees.as_ref()),
+            ("Effective date", notice.effective_date.as_ref()),
+            ("Date received", notice.date_received.as_ref()),
+        ]
+        .iter()
+        .filter_map(|(label, value)| value.map(|value| format!("{}: {}", label, value)))
+        .collect::<Vec<String>>()
+        .join("\n")
+    }
+
+    fn format_timestamp(timestamp: DateTime<Utc>) -> String {
+        timestamp.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+}
+
+impl NoticeExporter for AtomFeedExporter {
+    fn export(&self, notices: &WARNNotices) -> String {
+        let ids = NoticeId::unique_ids(&notices.notices);
+        let entries = ids
+            .iter()
+            .zip(notices.notices.iter())
+            .map(|(id, notice)| self.render_entry(id, notice))
+            .collect::<String>();
+
+        // The feed was last updated when its most recent entry was
+        let feed_updated = ids
+            .iter()
+            .filter_map(|id| self.state.get(id).map(|entry| entry.updated))
+            .max()
+            .unwrap_or(self.generated_at);
+
+        let mut feed = String::new();
+        feed.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+        feed.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+        feed.push_str(&format!("  <id>{}</id>\n", escape_xml(&self.feed_id)));
+        feed.push_str(&format!("  <title>{}</title>\n", escape_xml(&self.title)));
+        feed.push_str(&format!(
+            "  <updated>{}</updated>\n",
+            Self::format_timestamp(feed_updated)
+        ));
+        feed.push_str(&format!(
+            "  <author><name>{}</name></author>\n",
+            escape_xml(&self.title)
+        ));
+        feed.push_str(&entries);
+        feed.push_str("</feed>\n");
+        feed
+    }
+}
@@ -0,0 +1,94 @@
+//! Exports notices as a GeoJSON (RFC 7946) FeatureCollection for mapping tools.
+//! Each geocoded site of a notice becomes a Point feature, so a notice listing
+//! several towns appears once in each of them.
+use crate::{
+    employee_counts::EmployeeCounts,
+    exporters::{escape_json, NoticeExporter},
+    location_parser::LocationParser,
+    notice_id::NoticeId,
+    notices::{NoticeLocation, WARNNotice, WARNNotices},
+};
+
+/// Renders the geocoded locations of notices. Massachusetts sites that could
+/// not be geocoded are left out. Notices from other states can't be geocoded
+/// offline, so their sites are rendered with a null geometry.
+pub struct GeoJsonExporter {}
+
+impl GeoJsonExporter {
+    pub fn new() -> GeoJsonExporter {
+        GeoJsonExporter {}
+    }
+
+    fn render_feature(id: &str, notice: &WARNNotice, location: &NoticeLocation) -> String {
+        let affected_employees = EmployeeCounts::parse(notice.affected_employees())
+            .map_or("null".to_string(), |count| count.to_string());
+
+        // Fields the notice or gazetteer doesn't have are null rather than ""
+        let string_properties = [
+            ("notice_id", Some(id)),
+            ("firm_name", notice.firm_name.as_deref()),
+            ("firm_locations", notice.firm_locations.as_deref()),
+            ("effective_date", notice.effective_date.as_deref()),
+            ("date_received", notice.date_received.as_deref()),
+            ("town", location.town.as_deref()),
+            ("neighbourhood", location.neighbourhood.as_deref()),
+            ("county", location.county.as_deref()),
+            ("region", location.region.as_deref()),
+        ]
+        .iter()
+        .map(|(name, value)| match value {
+            Some(value) => format!("\"{}\":\"{}\"", name, escape_json(value)),
+            None => format!("\"{}\":null", name),
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+
+        let geometry = match Self::is_geocoded(location) {
+            true => format!(
+                "{{\"type\":\"Point\",\"coordinates\":[{},{}]}}",
+                location.longitude(),
+                location.latitude()
+            ),
+            false => "null".to_string(),
+        };
+
+        format!(
+            "{{\"type\":\"Feature\",\"geometry\":{},\
+             \"properties\":{{{},\"affected_employees\":{}}}}}",
+            geometry, string_properties, affected_employees
+        )
+    }
+
+    fn is_geocoded(location: &NoticeLocation) -> bool {
+        location.has_latitude() && location.has_longitude()
+    }
+}
+
+impl Default for GeoJsonExporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl NoticeExporter for GeoJsonExporter {
+    fn export(&self, notices: &WARNNotices) -> String {
+        let ids = NoticeId::unique_ids(&notices.notices);
+        let features = ids
+            .iter()
+            .zip(notices.notices.iter())
+            .flat_map(|(id, notice)| {
+                let is_massachusetts = LocationParser::is_massachusetts(notice);
+                LocationParser::locations(notice)
+                    .into_iter()
+                    .filter(|location| !is_massachusetts || Self::is_geocoded(location))
+                    .map(|location| Self::render_feature(id, notice, &location))
+                    .collect::<Vec<String>>()
+            })
+            .collect::<Vec<String>>();
+
+        format!(
+            "{{\"type\":\"FeatureCollection\",\"features\":[\n{}\n]}}\n",
+            features.join(",\n")
+        )
+    }
+}
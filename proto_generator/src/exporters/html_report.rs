@@ -0,0 +1,212 @@
+//! Renders notices into a self-contained HTML report. The report is built
+//! from a template so its layout can be customised without code changes.
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    employee_counts::EmployeeCounts,
+    exporters::{escape_xml, NoticeExporter},
+    notice_dates::NoticeDates,
+    notices::{WARNNotice, WARNNotices},
+};
+
+/// Template used when the caller does not provide their own
+pub const DEFAULT_REPORT_TEMPLATE: &str = include_str!("../../templates/report.html");
+const UNKNOWN_MONTH: &str = "Unknown";
+
+/// Renders the report for a fiscal year's notices. The notices submitted this
+/// past week are given their own section.
+pub struct HtmlReportExporter {
+    title: String,
+    generated_at: DateTime<Utc>,
+    weekly_notices: WARNNotices,
+    template: String,
+}
+
+impl HtmlReportExporter {
+    /// # Params
+    /// * title - Title of the report
+    /// * generated_at - When the report was generated
+    /// * weekly_notices - Notices submitted this past week
+    /// * template - Template to render. The default template is used if None.
+    ///   See the default template for the placeholders available.
+    pub fn new(
+        title: String,
+        generated_at: DateTime<Utc>,
+        weekly_notices: WARNNotices,
+        template: Option<String>,
+    ) -> HtmlReportExporter {
+        HtmlReportExporter {
+            title,
+            generated_at,
+            weekly_notices,
+            template: template.unwrap_or_else(|| DEFAULT_REPORT_TEMPLATE.to_string()),
+        }
+    }
+
+    fn render_notices_table(notices: &WARNNotices) -> String {
+        let rows = notices
+            .notices
+            .iter()
+            .map(Self::render_notice_row)
+            .collect::<String>();
+
+        format!(
+            "<table class=\"sortable\">\n\
+             <thead><tr><th>Company</th><th>Location(s)</th><th>Affected Employees</th>\
+             <th>Effective Date</th><th>Date Received</th></tr></thead>\n\
+             <tbody>\n{}</tbody>\n</table>",
+            rows
+        )
+    }
+
+    fn render_notice_row(notice: &WARNNotice) -> String {
+        format!(
+            "<tr><td>{}</td><td>{}</td>{}{}{}</tr>\n",
+            escape_xml(notice.firm_name()),
+            escape_xml(notice.firm_locations()),
+            Self::render_sortable_cell(
+                notice.affected_employees(),
+                EmployeeCounts::parse(notice.affected_employees()).map(|count| count.to_string())
+            ),
+            Self::render_date_cell(notice.effective_date()),
+            Self::render_date_cell(notice.date_received()),
+        )
+    }
+
+    /// Dates are written however the state entered them, so sort on the
+    /// parsed date instead of the text
+    fn render_date_cell(raw_date: &str) -> String {
+        Self::render_sortable_cell(
+            raw_date,
+            NoticeDates::parse(raw_date).map(|date| date.to_string()),
+        )
+    }
+
+    fn render_sortable_cell(text: &str, sort_value: Option<String>) -> String {
+        match sort_value {
+            Some(sort_value) => format!(
+                "<td data-sort=\"{}\">{}</td>",
+                escape_xml(&sort_value),
+                escape_xml(text)
+            ),
+            None => format!("<td>{}</td>", escape_xml(text)),
+        }
+    }
+
+    /// Number of notices and affected employees for each month notices were
+    /// received in. Notices without a date received are grouped under Unknown.
+    fn render_monthly_totals_table(notices: &WARNNotices) -> String {
+        let mut monthly_totals: BTreeMap<NaiveDate, (usize, u32)> = BTreeMap::new();
+        let mut unknown_totals: (usize, u32) = (0, 0);
+
+        for notice in notices.notices.iter() {
+            let employees = EmployeeCounts::parse(notice.affected_employees()).unwrap_or(0);
+            let totals = match NoticeDates::parse_month(notice.date_received()) {
+                Some(month) => monthly_totals.entry(month).or_insert((0, 0)),
+                None => &mut unknown_totals,
+            };
+            totals.0 += 1;
+            totals.1 += employees;
+        }
+
+        let mut rows = monthly_totals
+            .iter()
+            .map(|(month, (count, employees))| {
+                format!(
+                    "<tr><td data-sort=\"{}\">{}</td><td>{}</td><td>{}</td></tr>\n",
+                    month.format("%Y-%m"),
+                    month.format("%B %Y"),
+                    count,
+                    employees
+                )
+            })
+            .collect::<String>();
+
+        if unknown_totals.0 > 0 {
+            rows.push_str(&format!(
+                "<tr><td data-sort=\"9999\">{}</td><td>{}</td><td>{}</td></tr>\n",
+                UNKNOWN_MONTH, unknown_totals.0, unknown_totals.1
+            ));
+        }
+
+        format!(
+            "<table class=\"sortable\">\n\
+             <thead><tr><th>Month</th><th>Notices</th><th>Affected Employees</th></tr></thead>\n\
+             <tbody>\n{}</tbody>\n</table>",
+            rows
+        )
+    }
+
+    /// Replaces every `{{name}}` placeholder in a single pass, so text taken
+    /// from the notices is never treated as a placeholder itself. Unknown
+    /// placeholders are left as is.
+    fn fill_template(template: &str, placeholders: &HashMap<&str, String>) -> String {
+        let mut filled = String::with_capacity(template.len());
+        let mut remaining = template;
+
+        while let Some(start) = remaining.find("{{") {
+            filled.push_str(&remaining[..start]);
+            let after_start = &remaining[start + 2..];
+            match after_start.find("}}") {
+                Some(end) => {
+                    let name = after_start[..end].trim();
+                    match placeholders.get(name) {
+                        Some(value) => filled.push_str(value),
+                        None => filled.push_str(&remaining[start..start + end + 4]),
+                    }
+                    remaining = &after_start[end + 2..];
+                }
+                None => {
+                    filled.push_str(&remaining[start..]);
+                    remaining = "";
+                }
+            }
+        }
+        filled.push_str(remaining);
+
+        filled
+    }
+}
+
+impl NoticeExporter for HtmlReportExporter {
+    fn export(&self, notices: &WARNNotices) -> String {
+        let total_affected_employees = EmployeeCounts::total(
+            notices
+                .notices
+                .iter()
+                .map(|notice| notice.affected_employees()),
+        );
+
+        let placeholders = [
+            ("title", escape_xml(&self.title)),
+            (
+                "generated_at",
+                self.generated_at.format("%B %-d, %Y %H:%M UTC").to_string(),
+            ),
+            ("notice_count", notices.notices.len().to_string()),
+            (
+                "weekly_notice_count",
+                self.weekly_notices.notices.len().to_string(),
+            ),
+            (
+                "total_affected_employees",
+                total_affected_employees.to_string(),
+            ),
+            (
+                "weekly_notices_table",
+                Self::render_notices_table(&self.weekly_notices),
+            ),
+            (
+                "monthly_totals_table",
+                Self::render_monthly_totals_table(notices),
+            ),
+            ("all_notices_table", Self::render_notices_table(notices)),
+        ]
+        .into_iter()
+        .collect::<HashMap<&str, String>>();
+
+        Self::fill_template(&self.template, &placeholders)
+    }
+}
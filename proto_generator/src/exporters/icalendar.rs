@@ -0,0 +1,131 @@
+//! Exports the effective dates of notices as an iCalendar (RFC 5545) document
+//! with an all-day event per notice
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+
+use crate::{
+    exporters::NoticeExporter,
+    notice_dates::NoticeDates,
+    notice_id::NoticeId,
+    notices::{WARNNotice, WARNNotices},
+};
+
+const PRODUCT_ID: &str = "-//WARN-Scraper_MA//WARN Notices//EN";
+const UID_DOMAIN: &str = "warn-scraper";
+/// Content lines longer than this many octets must be folded
+const MAX_LINE_OCTETS: usize = 75;
+const LINE_ENDING: &str = "\r\n";
+
+/// Renders notices into an iCalendar document. Notices without an effective
+/// date that can be understood are left out.
+pub struct ICalendarExporter {
+    calendar_name: String,
+    generated_at: DateTime<Utc>,
+}
+
+impl ICalendarExporter {
+    /// # Params
+    /// * calendar_name - Name calendar applications show for the calendar
+    /// * generated_at - When the calendar was generated
+    pub fn new(calendar_name: String, generated_at: DateTime<Utc>) -> ICalendarExporter {
+        ICalendarExporter {
+            calendar_name,
+            generated_at,
+        }
+    }
+
+    fn render_event(
+        &self,
+        notice: &WARNNotice,
+        filing_id: &str,
+        effective_date: NaiveDate,
+    ) -> Vec<String> {
+        let day_after = effective_date + Duration::days(1);
+        vec![
+            "BEGIN:VEVENT".to_string(),
+            // The uid is all calendar applications use to match an event on
+            // re-import, so it must not change between scrapes, even when the
+            // effective date is rescheduled
+            format!("UID:{}@{}", filing_id, UID_DOMAIN),
+            format!("DTSTAMP:{}", self.generated_at.format("%Y%m%dT%H%M%SZ")),
+            format!("DTSTART;VALUE=DATE:{}", effective_date.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", day_after.format("%Y%m%d")),
+            format!(
+                "SUMMARY:{}",
+                Self::escape_text(&format!("WARN layoff: {}", notice.firm_name()))
+            ),
+            format!(
+                "DESCRIPTION:{}",
+                Self::escape_text(&Self::event_description(notice))
+            ),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]
+    }
+
+    /// One line per field present on the notice
+    fn event_description(notice: &WARNNotice) -> String {
+        [
+            ("Company", notice.firm_name.as_ref()),
+            ("Location(s)", notice.firm_locations.as_ref()),
+            ("Affected employees", notice.affected_employees.as_ref()),
+            ("Date received", notice.date_received.as_ref()),
+        ]
+        .iter()
+        .filter_map(|(label, value)| value.map(|value| format!("{}: {}", label, value)))
+        .collect::<Vec<String>>()
+        .join("\n")
+    }
+
+    /// Escapes the characters with special meaning in TEXT values
+    fn escape_text(raw: &str) -> String {
+        raw.replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace("\r\n", "\\n")
+            .replace('\n', "\\n")
+    }
+
+    /// Splits a content line into multiple lines of at most 75 octets. Each
+    /// continuation line starts with a space.
+    fn fold_line(line: &str) -> String {
+        let mut folded = String::with_capacity(line.len());
+        let mut line_octets = 0;
+        for c in line.chars() {
+            if line_octets + c.len_utf8() > MAX_LINE_OCTETS {
+                folded.push_str(LINE_ENDING);
+                folded.push(' ');
+                // The leading space counts towards the continuation line
+                line_octets = 1;
+            }
+            folded.push(c);
+            line_octets += c.len_utf8();
+        }
+        folded
+    }
+}
+
+impl NoticeExporter for ICalendarExporter {
+    fn export(&self, notices: &WARNNotices) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            format!("PRODID:{}", PRODUCT_ID),
+            "CALSCALE:GREGORIAN".to_string(),
+            format!("X-WR-CALNAME:{}", Self::escape_text(&self.calendar_name)),
+        ];
+
+        // Uids must be unique within the calendar, even for identical filings
+        let filing_ids = NoticeId::unique_filing_ids(&notices.notices);
+        for (notice, filing_id) in notices.notices.iter().zip(filing_ids.iter()) {
+            if let Some(effective_date) = NoticeDates::parse(notice.effective_date()) {
+                lines.append(&mut self.render_event(notice, filing_id, effective_date));
+            }
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        lines
+            .iter()
+            .map(|line| Self::fold_line(line) + LINE_ENDING)
+            .collect()
+    }
+}
@@ -0,0 +1,105 @@
+//! Renders notices as Markdown for pasting into wikis and chat
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::{
+    employee_counts::EmployeeCounts,
+    exporters::NoticeExporter,
+    notice_dates::NoticeDates,
+    notices::{WARNNotice, WARNNotices},
+};
+
+const UNKNOWN_MONTH_HEADING: &str = "Unknown Effective Date";
+
+/// Renders a header with counts followed by a table of notices for each month
+/// notices take effect in
+pub struct MarkdownExporter {
+    title: String,
+    scraped_at: DateTime<Utc>,
+}
+
+impl MarkdownExporter {
+    /// # Params
+    /// * title - Heading of the document
+    /// * scraped_at - When the notices were scraped
+    pub fn new(title: String, scraped_at: DateTime<Utc>) -> MarkdownExporter {
+        MarkdownExporter { title, scraped_at }
+    }
+
+    fn render_header(&self, notices: &WARNNotices) -> String {
+        let total_affected_employees = EmployeeCounts::total(
+            notices
+                .notices
+                .iter()
+                .map(|notice| notice.affected_employees()),
+        );
+
+        format!(
+            "# {}\n\n\
+             * Notices: {}\n\
+             * Affected employees: {}\n\
+             * Scraped: {}\n",
+            Self::escape_inline(&self.title),
+            notices.notices.len(),
+            total_affected_employees,
+            self.scraped_at.format("%Y-%m-%d %H:%M UTC")
+        )
+    }
+
+    fn render_month(heading: &str, notices: &[&WARNNotice]) -> String {
+        let mut month = format!(
+            "\n## {} ({} notices)\n\n\
+             | Company | Location(s) | Affected Employees | Effective Date | Date Received |\n\
+             | --- | --- | --- | --- | --- |\n",
+            Self::escape_inline(heading),
+            notices.len()
+        );
+
+        for notice in notices {
+            month.push_str(&format!(
+                "| {} | {} | {} | {} | {} |\n",
+                Self::escape_inline(notice.firm_name()),
+                Self::escape_inline(notice.firm_locations()),
+                Self::escape_inline(notice.affected_employees()),
+                Self::escape_inline(notice.effective_date()),
+                Self::escape_inline(notice.date_received()),
+            ));
+        }
+
+        month
+    }
+
+    /// Pipes would end a table cell early and new lines would end the row or
+    /// heading the text is on
+    fn escape_inline(raw: &str) -> String {
+        raw.replace('|', "\\|").replace(['\r', '\n'], " ")
+    }
+}
+
+impl NoticeExporter for MarkdownExporter {
+    fn export(&self, notices: &WARNNotices) -> String {
+        let mut by_effective_month: BTreeMap<NaiveDate, Vec<&WARNNotice>> = BTreeMap::new();
+        let mut unknown_month: Vec<&WARNNotice> = Vec::new();
+
+        for notice in notices.notices.iter() {
+            match NoticeDates::parse_month(notice.effective_date()) {
+                Some(month) => by_effective_month.entry(month).or_default().push(notice),
+                None => unknown_month.push(notice),
+            }
+        }
+
+        let mut markdown = self.render_header(notices);
+        for (month, month_notices) in by_effective_month.iter() {
+            markdown.push_str(&Self::render_month(
+                &month.format("%B %Y").to_string(),
+                month_notices,
+            ));
+        }
+        if !unknown_month.is_empty() {
+            markdown.push_str(&Self::render_month(UNKNOWN_MONTH_HEADING, &unknown_month));
+        }
+
+        markdown
+    }
+}
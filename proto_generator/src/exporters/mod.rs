@@ -0,0 +1,49 @@
+//! Exporters turn a grouping of notices into documents that tools other than
+//! this CLI can consume (feed readers, calendars, ...).
+pub mod atom_feed;
+pub mod geojson;
+pub mod html_report;
+pub mod icalendar;
+pub mod markdown;
+
+use crate::notices::WARNNotices;
+
+/// Common interface for every export format
+pub trait NoticeExporter {
+    /// Renders the notices into the exported document
+    fn export(&self, notices: &WARNNotices) -> String;
+}
+
+/// Escapes the characters that are not allowed in XML / HTML text and
+/// attribute values
+pub(crate) fn escape_xml(raw: &str) -> String {
+    raw.chars()
+        .fold(String::with_capacity(raw.len()), |mut escaped, c| {
+            match c {
+                '&' => escaped.push_str("&amp;"),
+                '<' => escaped.push_str("&lt;"),
+                '>' => escaped.push_str("&gt;"),
+                '"' => escaped.push_str("&quot;"),
+                '\'' => escaped.push_str("&apos;"),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
+
+/// Escapes a string for use inside a JSON string literal
+pub(crate) fn escape_json(raw: &str) -> String {
+    raw.chars()
+        .fold(String::with_capacity(raw.len()), |mut escaped, c| {
+            match c {
+                '"' => escaped.push_str("\\\""),
+                '\\' => escaped.push_str("\\\\"),
+                '\n' => escaped.push_str("\\n"),
+                '\r' => escaped.push_str("\\r"),
+                '\t' => escaped.push_str("\\t"),
+                c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+                _ => escaped.push(c),
+            }
+            escaped
+        })
+}
@@ -0,0 +1,62 @@
+//! Offline lookup of the county, region and approximate centre of each
+//! Massachusetts city and town, from a gazetteer bundled with the crate
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::notices::NoticeLocation;
+
+const GAZETTEER: &str = include_str!("../data/ma_gazetteer.csv");
+
+/// Everything the gazetteer knows about a town
+#[derive(Clone, Debug, PartialEq)]
+pub struct GazetteerEntry {
+    pub town: String,
+    pub county: String,
+    pub region: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+pub struct Gazetteer {}
+
+impl Gazetteer {
+    /// # Params
+    /// * town - The official name of the town, as set by the location parser
+    pub fn lookup(town: &str) -> Option<&'static GazetteerEntry> {
+        Self::entries().get(&town.to_lowercase())
+    }
+
+    /// Attaches the county, region and coordinates of the location's town.
+    /// Locations without a recognised town are left untouched.
+    pub fn geocode(location: &mut NoticeLocation) {
+        if let Some(entry) = Self::lookup(location.town()) {
+            location.set_county(entry.county.clone());
+            location.set_region(entry.region.clone());
+            location.set_latitude(entry.latitude);
+            location.set_longitude(entry.longitude);
+        }
+    }
+
+    fn entries() -> &'static HashMap<String, GazetteerEntry> {
+        static ENTRIES: OnceLock<HashMap<String, GazetteerEntry>> = OnceLock::new();
+        ENTRIES.get_or_init(|| {
+            GAZETTEER
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .filter_map(Self::parse_entry)
+                .map(|entry| (entry.town.to_lowercase(), entry))
+                .collect()
+        })
+    }
+
+    fn parse_entry(line: &str) -> Option<GazetteerEntry> {
+        let mut columns = line.split(',').map(str::trim);
+        Some(GazetteerEntry {
+            town: columns.next()?.to_string(),
+            county: columns.next()?.to_string(),
+            region: columns.next()?.to_string(),
+            latitude: columns.next()?.parse().ok()?,
+            longitude: columns.next()?.parse().ok()?,
+        })
+    }
+}
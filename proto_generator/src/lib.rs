@@ -1,4 +1,15 @@
+pub mod company_resolver;
+pub mod company_timeline;
+pub mod employee_counts;
+pub mod exporters;
+pub mod gazetteer;
+pub mod location_parser;
+pub mod notice_classifier;
 pub mod notice_collector;
+pub mod notice_dates;
+pub mod notice_id;
+pub mod notice_statistics;
+pub mod notice_store;
 
 // Allows including via proto_generator::export
 // The build script names each module the same name as the <name>.proto
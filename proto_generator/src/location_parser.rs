@@ -0,0 +1,215 @@
+//! Parses the free-form firm_locations text of a notice into individual
+//! Massachusetts towns. Both the weekly page and the yearly spreadsheet put
+//! every site in a single string, i.e. "Boston, Cambridge and Worcester".
+use std::{collections::HashMap, sync::OnceLock};
+
+use crate::{
+    gazetteer::Gazetteer,
+    notices::{NoticeLocation, WARNNotice, WARNNotices},
+};
+
+const MUNICIPALITIES: &str = include_str!("../data/ma_municipalities.txt");
+const PLACE_ALIASES: &str = include_str!("../data/ma_place_aliases.csv");
+const NEIGHBOURHOOD_ALIAS_KIND: &str = "neighbourhood";
+const MASSACHUSETTS_STATE_CODE: &str = "MA";
+
+/// Separators between sites, matched ignoring case
+const SITE_SEPARATORS: [&str; 6] = [",", ";", "/", "\n", " & ", " and "];
+/// Parts of a location that name the state rather than a town
+const STATE_NAMES: [&str; 4] = ["ma", "mass", "massachusetts", "usa"];
+const TOWN_PREFIXES: [&str; 2] = ["city of ", "town of "];
+
+/// What a normalised name refers to
+#[derive(Clone, Debug)]
+struct KnownPlace {
+    town: String,
+    neighbourhood: Option<String>,
+}
+
+pub struct LocationParser {}
+
+impl LocationParser {
+    /// Splits the text into its sites and matches each against the bundled
+    /// list of Massachusetts municipalities. Recognised towns are geocoded.
+    /// # Return
+    /// * A location for each site, with the town set when it was recognised
+    /// * Parts that only name the state (i.e. "MA") or a street address are
+    ///   dropped when another part of the text was recognised
+    pub fn parse(raw_locations: &str) -> Vec<NoticeLocation> {
+        let sites = Self::split_sites(raw_locations)
+            .into_iter()
+            .filter(|site| !STATE_NAMES.contains(&Self::normalise(site).as_str()))
+            .map(|site| Self::parse_site(&site))
+            .collect::<Vec<NoticeLocation>>();
+
+        match sites.iter().any(|site| site.has_town()) {
+            true => sites.into_iter().filter(|site| site.has_town()).collect(),
+            false => sites,
+        }
+    }
+
+    /// Fills in the structured locations of every Massachusetts notice that
+    /// does not already have them
+    pub fn annotate_notices(notices: &mut WARNNotices) {
+        notices
+            .notices
+            .iter_mut()
+            .filter(|notice| notice.locations.is_empty() && Self::is_massachusetts(notice))
+            .for_each(Self::annotate_notice);
+    }
+
+    /// # Return
+    /// The structured locations of the notice, parsing its text when it has
+    /// none. Only Massachusetts towns are known, so a notice from another
+    /// state without locations gets a single location holding its text.
+    pub fn locations(notice: &WARNNotice) -> Vec<NoticeLocation> {
+        if !notice.locations.is_empty() {
+            return notice.locations.clone();
+        }
+        if Self::is_massachusetts(notice) {
+            return Self::parse(notice.firm_locations());
+        }
+
+        let raw_locations = notice.firm_locations().trim();
+        match raw_locations.is_empty() {
+            true => Vec::new(),
+            false => {
+                let mut location = NoticeLocation::new();
+                location.set_raw(raw_locations.to_string());
+                vec![location]
+            }
+        }
+    }
+
+    /// Notices without a state come from the Massachusetts scraper
+    pub fn is_massachusetts(notice: &WARNNotice) -> bool {
+        let state = notice.state().trim();
+        state.is_empty() || state.eq_ignore_ascii_case(MASSACHUSETTS_STATE_CODE)
+    }
+
+    pub fn annotate_notice(notice: &mut WARNNotice) {
+        notice.locations = Self::parse(notice.firm_locations());
+    }
+
+    fn parse_site(site: &str) -> NoticeLocation {
+        let mut location = NoticeLocation::new();
+        location.set_raw(site.to_string());
+
+        if let Some(place) = Self::known_places().get(&Self::normalise(site)) {
+            location.set_town(place.town.clone());
+            if let Some(neighbourhood) = &place.neighbourhood {
+                location.set_neighbourhood(neighbourhood.clone());
+            }
+            Gazetteer::geocode(&mut location);
+        }
+
+        location
+    }
+
+    /// Splits on every separator. Matching is done on an ascii lowercase copy,
+    /// which has the same byte offsets as the original text.
+    fn split_sites(raw_locations: &str) -> Vec<String> {
+        let lowercase = raw_locations.to_ascii_lowercase();
+        let mut sites = Vec::new();
+        let mut site_start = 0;
+        let mut index = 0;
+
+        while index < lowercase.len() {
+            match SITE_SEPARATORS
+                .iter()
+                .find(|separator| lowercase[index..].starts_with(*separator))
+            {
+                Some(separator) => {
+                    sites.push(&raw_locations[site_start..index]);
+                    index += separator.len();
+                    site_start = index;
+                }
+                None => {
+                    index += lowercase[index..].chars().next().map_or(1, char::len_utf8);
+                }
+            }
+        }
+        sites.push(&raw_locations[site_start..]);
+
+        sites
+            .into_iter()
+            .map(|site| site.trim().to_string())
+            .filter(|site| !site.is_empty())
+            .collect()
+    }
+
+    /// Lowercases the name and strips the punctuation, state and zip code
+    /// that are often written after a town
+    fn normalise(name: &str) -> String {
+        let mut words = name
+            .to_lowercase()
+            .replace('.', "")
+            .split_whitespace()
+            .map(|word| match word {
+                "mt" => "mount".to_string(),
+                _ => word.to_string(),
+            })
+            .collect::<Vec<String>>();
+
+        while let Some(last_word) = words.last() {
+            let is_zip_code = last_word.chars().all(|c| c.is_ascii_digit() || c == '-')
+                && last_word.chars().any(|c| c.is_ascii_digit());
+            if words.len() > 1 && (is_zip_code || STATE_NAMES.contains(&last_word.as_str())) {
+                words.pop();
+            } else {
+                break;
+            }
+        }
+
+        let normalised = words.join(" ");
+        TOWN_PREFIXES
+            .iter()
+            .find_map(|prefix| normalised.strip_prefix(prefix))
+            .map(str::to_string)
+            .unwrap_or(normalised)
+    }
+
+    /// Every normalised name that refers to a Massachusetts town
+    fn known_places() -> &'static HashMap<String, KnownPlace> {
+        static KNOWN_PLACES: OnceLock<HashMap<String, KnownPlace>> = OnceLock::new();
+        KNOWN_PLACES.get_or_init(|| {
+            let mut places: HashMap<String, KnownPlace> = Self::data_lines(MUNICIPALITIES)
+                .map(|town| {
+                    (
+                        Self::normalise(town),
+                        KnownPlace {
+                            town: town.to_string(),
+                            neighbourhood: None,
+                        },
+                    )
+                })
+                .collect();
+
+            for alias_line in Self::data_lines(PLACE_ALIASES) {
+                let mut columns = alias_line.split(',').map(str::trim);
+                if let (Some(alias), Some(town), Some(kind)) =
+                    (columns.next(), columns.next(), columns.next())
+                {
+                    let neighbourhood =
+                        (kind == NEIGHBOURHOOD_ALIAS_KIND).then(|| alias.to_string());
+                    places.insert(
+                        Self::normalise(alias),
+                        KnownPlace {
+                            town: town.to_string(),
+                            neighbourhood,
+                        },
+                    );
+                }
+            }
+
+            places
+        })
+    }
+
+    /// Lines of a bundled data file, skipping comments and blank lines
+    fn data_lines(data: &'static str) -> impl Iterator<Item = &'static str> {
+        data.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    }
+}
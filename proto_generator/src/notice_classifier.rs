@@ -0,0 +1,230 @@
+//! Tells whether a notice is of a plant closure, a partial layoff or a
+//! relocation. Fields stating the kind (the Massachusetts weekly notice type,
+//! the New York classification) are trusted first; otherwise the notice's
+//! free text is searched for keywords.
+use crate::notices::{
+    ClassificationConfidence, NoticeClassification, NoticeKind, WARNNotice, WARNNotices,
+};
+
+/// Phrases telling each kind, matched ignoring case at word boundaries.
+/// Where phrases overlap the longest wins, so "partial closing" is a layoff.
+const KIND_KEYWORDS: [(NoticeKind, &[&str]); 3] = [
+    (
+        NoticeKind::KIND_CLOSURE,
+        &[
+            "closure",
+            "closing",
+            "close",
+            "closed",
+            "closes",
+            "shutdown",
+            "shut down",
+            "shutting down",
+            "cease operations",
+            "ceasing operations",
+            "ceased operations",
+            "out of business",
+        ],
+    ),
+    (
+        NoticeKind::KIND_LAYOFF,
+        &[
+            "layoff",
+            "layoffs",
+            "lay off",
+            "lay-off",
+            "laid off",
+            "reduction in force",
+            "workforce reduction",
+            "downsizing",
+            "restructuring",
+            "partial closure",
+            "partial closing",
+            "partial shutdown",
+        ],
+    ),
+    (
+        NoticeKind::KIND_RELOCATION,
+        &[
+            "relocation",
+            "relocating",
+            "relocate",
+            "relocated",
+            "moving operations",
+            "transfer of operations",
+            "transferring operations",
+        ],
+    ),
+];
+
+/// Kinds in the order they win when a text mentions several. A relocation
+/// closes a site and a closure lays everyone off, so the most specific wins.
+const KIND_PRECEDENCE: [NoticeKind; 3] = [
+    NoticeKind::KIND_RELOCATION,
+    NoticeKind::KIND_CLOSURE,
+    NoticeKind::KIND_LAYOFF,
+];
+
+pub struct NoticeClassifier {}
+
+impl NoticeClassifier {
+    /// # Return
+    /// * The kind stated by the notice with high confidence, if it states one
+    /// * Else the kind its text mentions, with medium confidence if only one
+    ///   kind is mentioned and low confidence if several are
+    /// * Else unclassified, with no confidence
+    pub fn classify(notice: &WARNNotice) -> NoticeClassification {
+        let stated = Self::stated_fields(notice)
+            .into_iter()
+            .find_map(|(field, text)| {
+                let kinds = Self::mentioned_kinds(text);
+                let kind = Self::most_specific(kinds.iter().map(|(kind, _)| *kind))?;
+                Some(Self::classification(
+                    kind,
+                    ClassificationConfidence::CONFIDENCE_HIGH,
+                    format!("{}: {}", field, text),
+                ))
+            });
+        if let Some(classification) = stated {
+            return classification;
+        }
+
+        let mentions = Self::free_text(notice)
+            .into_iter()
+            .flat_map(|(field, text)| {
+                Self::mentioned_kinds(&text)
+                    .into_iter()
+                    .map(move |(kind, keyword)| (kind, format!("{} mentions {}", field, keyword)))
+            })
+            .collect::<Vec<(NoticeKind, String)>>();
+        let kind = match Self::most_specific(mentions.iter().map(|(kind, _)| *kind)) {
+            Some(kind) => kind,
+            None => {
+                return Self::classification(
+                    NoticeKind::KIND_UNCLASSIFIED,
+                    ClassificationConfidence::CONFIDENCE_NONE,
+                    String::new(),
+                )
+            }
+        };
+        let confidence = match mentions.iter().all(|(mentioned, _)| *mentioned == kind) {
+            true => ClassificationConfidence::CONFIDENCE_MEDIUM,
+            false => ClassificationConfidence::CONFIDENCE_LOW,
+        };
+        let basis = mentions
+            .into_iter()
+            .find(|(mentioned, _)| *mentioned == kind)
+            .map(|(_, basis)| basis)
+            .unwrap_or_default();
+
+        Self::classification(kind, confidence, basis)
+    }
+
+    /// Classifies every notice that is not classified yet
+    pub fn annotate_notices(notices: &mut WARNNotices) {
+        notices
+            .notices
+            .iter_mut()
+            .filter(|notice| notice.classification.is_none())
+            .for_each(Self::annotate_notice);
+    }
+
+    pub fn annotate_notice(notice: &mut WARNNotice) {
+        notice.classification = Some(Self::classify(notice)).into();
+    }
+
+    /// Fields whose only purpose is to state the kind, by name
+    fn stated_fields(notice: &WARNNotice) -> Vec<(&'static str, &str)> {
+        let mut fields = Vec::new();
+        if notice.has_notice_type() {
+            fields.push(("notice_type", notice.notice_type()));
+        }
+        if let Some(details) = notice.new_york.as_ref() {
+            if details.has_classification() {
+                fields.push(("classification", details.classification()));
+            }
+        }
+        fields
+    }
+
+    /// Free text fields that may mention the kind, by name
+    fn free_text(notice: &WARNNotice) -> Vec<(String, String)> {
+        let mut texts = Vec::new();
+        if notice.has_reason_for_dislocation() {
+            texts.push((
+                "reason_for_dislocation".to_string(),
+                notice.reason_for_dislocation().to_string(),
+            ));
+        }
+        if let Some(details) = notice.new_york.as_ref() {
+            if details.has_reason_stated_for_filing() {
+                texts.push((
+                    "reason_stated_for_filing".to_string(),
+                    details.reason_stated_for_filing().to_string(),
+                ));
+            }
+        }
+        let mut additional_fields = notice.additional_fields.iter().collect::<Vec<_>>();
+        additional_fields.sort();
+        for (label, value) in additional_fields {
+            texts.push((label.clone(), format!("{} {}", label, value)));
+        }
+        texts
+    }
+
+    /// # Return
+    /// Each kind the text mentions with the first keyword it was told by
+    fn mentioned_kinds(text: &str) -> Vec<(NoticeKind, &'static str)> {
+        let mut text = text.to_lowercase().chars().collect::<Vec<char>>();
+        let mut keywords = KIND_KEYWORDS
+            .iter()
+            .flat_map(|(kind, keywords)| keywords.iter().map(move |keyword| (*kind, *keyword)))
+            .collect::<Vec<(NoticeKind, &str)>>();
+        keywords.sort_by_key(|(_, keyword)| std::cmp::Reverse(keyword.len()));
+
+        let mut mentioned: Vec<(NoticeKind, &'static str)> = Vec::new();
+        for (kind, keyword) in keywords {
+            let keyword_chars = keyword.chars().collect::<Vec<char>>();
+            let mut found = false;
+            let mut idx = 0;
+            while idx + keyword_chars.len() <= text.len() {
+                let end = idx + keyword_chars.len();
+                let at_word_boundaries = (idx == 0 || !text[idx - 1].is_alphanumeric())
+                    && text.get(end).is_none_or(|c| !c.is_alphanumeric());
+                match at_word_boundaries && text[idx..end] == keyword_chars[..] {
+                    true => {
+                        // Blanked so shorter keywords it contains don't match
+                        text[idx..end].iter_mut().for_each(|c| *c = ' ');
+                        found = true;
+                        idx = end;
+                    }
+                    false => idx += 1,
+                }
+            }
+            if found && !mentioned.iter().any(|(mentioned, _)| *mentioned == kind) {
+                mentioned.push((kind, keyword));
+            }
+        }
+        mentioned
+    }
+
+    fn most_specific(kinds: impl Iterator<Item = NoticeKind> + Clone) -> Option<NoticeKind> {
+        KIND_PRECEDENCE
+            .into_iter()
+            .find(|kind| kinds.clone().any(|mentioned| mentioned == *kind))
+    }
+
+    fn classification(
+        kind: NoticeKind,
+        confidence: ClassificationConfidence,
+        basis: String,
+    ) -> NoticeClassification {
+        let mut classification = NoticeClassification::new();
+        classification.set_kind(kind);
+        classification.set_confidence(confidence);
+        if !basis.is_empty() {
+            classification.set_basis(basis);
+        }
+        classification
+    }
+}
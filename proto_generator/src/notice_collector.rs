@@ -1,6 +1,12 @@
-use std::default;
+use std::{collections::HashSet, default};
 
-use crate::notices::{WARNNotice, WARNNotices};
+use chrono::NaiveDate;
+
+use crate::{
+    notice_classifier::NoticeClassifier,
+    notice_dates::NoticeDates,
+    notices::{ClassificationConfidence, NoticeKind, WARNNotice, WARNNotices},
+};
 
 pub struct NoticeCollector {}
 
@@ -28,18 +34,75 @@ impl NoticeCollector {
         }
     }
 
-    /// Retrieves all notices that reference the company in question.
-    /// Ignores cases
+    /// Retrieves all notices that reference the company in question, by name
+    /// or company id. Ignores cases. Notices resolved to a company bring along
+    /// every notice of that company, whatever name it was filed under.
     pub fn search_notices_for_company(notices: WARNNotices, company_name_key: &str) -> WARNNotices {
+        let company_name_key_lower = company_name_key.to_lowercase();
+        let references_company = |notice: &WARNNotice| {
+            notice
+                .firm_name()
+                .to_lowercase()
+                .contains(&company_name_key_lower)
+                || notice
+                    .company_name()
+                    .to_lowercase()
+                    .contains(&company_name_key_lower)
+                || (notice.has_company_id() && notice.company_id() == company_name_key)
+        };
+        let company_ids = notices
+            .notices
+            .iter()
+            .filter(|notice| notice.has_company_id() && references_company(notice))
+            .map(|notice| notice.company_id().to_string())
+            .collect::<HashSet<String>>();
+
         let matches: Vec<WARNNotice> = notices
             .notices
             .into_iter()
             .filter(|notice| {
-                notice
-                    .firm_name()
-                    .to_lowercase()
-                    .contains(&company_name_key.to_lowercase())
+                references_company(notice)
+                    || (notice.has_company_id() && company_ids.contains(notice.company_id()))
+            })
+            .collect();
+
+        Self::to_notices_from_vec(matches)
+    }
+
+    /// Retrieves the notices classified as one of the kinds, or as any kind
+    /// if none are given, with at least the confidence given. Notices not
+    /// classified yet are classified first.
+    pub fn filter_by_classification(
+        notices: WARNNotices,
+        kinds: &[NoticeKind],
+        min_confidence: ClassificationConfidence,
+    ) -> WARNNotices {
+        let matches: Vec<WARNNotice> = notices
+            .notices
+            .into_iter()
+            .filter(|notice| {
+                let classification = match notice.classification.as_ref() {
+                    Some(classification) => classification.clone(),
+                    None => NoticeClassifier::classify(notice),
+                };
+                (kinds.is_empty() || kinds.contains(&classification.kind()))
+                    && classification.confidence() as i32 >= min_confidence as i32
+            })
+            .collect();
+
+        Self::to_notices_from_vec(matches)
+    }
+
+    /// Retrieves the notices received on or after the date. Notices without a
+    /// date received that can be understood are left out.
+    pub fn filter_received_since(notices: &WARNNotices, since: NaiveDate) -> WARNNotices {
+        let matches: Vec<WARNNotice> = notices
+            .notices
+            .iter()
+            .filter(|notice| {
+                NoticeDates::parse(notice.date_received()).is_some_and(|date| date >= since)
             })
+            .cloned()
             .collect();
 
         Self::to_notices_from_vec(matches)
@@ -0,0 +1,55 @@
+//! Utilities for interpreting the free-form date strings stored on notices.
+//! Dates from the yearly spreadsheet are ISO formatted, while the weekly page
+//! uses whatever the state typed in (i.e. 9/1/2023 or September 1, 2023).
+use chrono::{Datelike, NaiveDate};
+
+/// Formats seen across the weekly page and yearly spreadsheets. Two digit
+/// years are tried first, as %Y would read them as the first century.
+const KNOWN_DATE_FORMATS: [&str; 7] = [
+    "%Y-%m-%d",
+    "%m/%d/%y",
+    "%m/%d/%Y",
+    "%B %d, %Y",
+    "%b %d, %Y",
+    "%B %d %Y",
+    "%b. %d, %Y",
+];
+
+/// Some notices list a range or several dates in one field. The first date is
+/// the one we care about.
+const DATE_SEPARATORS: [&str; 5] = [" - ", " to ", " through ", ";", " and "];
+
+pub struct NoticeDates {}
+
+impl NoticeDates {
+    /// Attempts to interpret a date string from a notice.
+    /// # Return
+    /// * None if no known format matches
+    /// * The first date found in the string otherwise
+    pub fn parse(raw_date: &str) -> Option<NaiveDate> {
+        let trimmed = raw_date.trim();
+        if trimmed.is_empty() {
+            return None;
+        }
+
+        Self::parse_known_formats(trimmed).or_else(|| {
+            DATE_SEPARATORS
+                .iter()
+                .filter_map(|separator| trimmed.split(separator).next())
+                .filter(|first_part| first_part.len() < trimmed.len())
+                .find_map(|first_part| Self::parse_known_formats(first_part.trim()))
+        })
+    }
+
+    /// # Return
+    /// The first day of the month of the date, for grouping notices by month
+    pub fn parse_month(raw_date: &str) -> Option<NaiveDate> {
+        Self::parse(raw_date).and_then(|date| date.with_day(1))
+    }
+
+    fn parse_known_formats(raw_date: &str) -> Option<NaiveDate> {
+        KNOWN_DATE_FORMATS
+            .iter()
+            .find_map(|format| NaiveDate::parse_from_str(raw_date, format).ok())
+    }
+}
@@ -0,0 +1,146 @@
+//! Stable identifiers for notices.
+//! Exported documents (feeds, calendars) need ids that stay the same between
+//! scrapes so consumers update entries rather than duplicating them. The std
+//! hasher is not guaranteed to be stable between Rust releases, so FNV-1a is
+//! used instead.
+use crate::notices::WARNNotice;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+pub struct NoticeId {}
+
+impl NoticeId {
+    /// # Return
+    /// An id derived from the firm name and the notice's dates. Casing and
+    /// surrounding whitespace are ignored so minor edits on the state's end do
+    /// not create a new id.
+    pub fn stable_id(notice: &WARNNotice) -> String {
+        let key = [
+            notice.firm_name(),
+            notice.date_received(),
+            notice.effective_date(),
+        ]
+        .iter()
+        .map(|field| field.trim().to_lowercase())
+        .collect::<Vec<String>>()
+        .join("|");
+
+        format!("{:016x}", Self::fnv1a(key.as_bytes()))
+    }
+
+    /// # Return
+    /// An id for the filing itself, derived from the firm name, its locations,
+    /// the date it was received and its state. Unlike the stable id it leaves
+    /// out the effective date, which states reschedule after a notice is
+    /// filed. See [`NoticeId::unique_filing_ids`] for a firm filing twice for
+    /// the same site on the same day.
+    pub fn filing_id(notice: &WARNNotice) -> String {
+        let key = [
+            notice.firm_name(),
+            notice.firm_locations(),
+            notice.date_received(),
+            notice.state(),
+        ]
+        .iter()
+        .map(|field| field.split_whitespace().collect::<Vec<&str>>().join(" "))
+        .map(|field| field.to_lowercase())
+        .collect::<Vec<String>>()
+        .join("|");
+
+        format!("{:016x}", Self::fnv1a(key.as_bytes()))
+    }
+
+    /// # Return
+    /// A hash over every field of the notice. It changes whenever any field
+    /// of the notice changes.
+    pub fn content_fingerprint(notice: &WARNNotice) -> String {
+        // Sorted, as the map's iteration order changes from run to run
+        let mut additional_fields = notice
+            .additional_fields
+            .iter()
+            .map(|(label, value)| format!("{}={}", label, value))
+            .collect::<Vec<String>>();
+        additional_fields.sort();
+
+        let content = [
+            notice.firm_name(),
+            notice.firm_locations(),
+            notice.affected_employees(),
+            notice.effective_date(),
+            notice.date_received(),
+            notice.reason_for_dislocation(),
+            notice.notice_type(),
+            notice.union_representation(),
+            notice.contact(),
+            &additional_fields.join("|"),
+        ]
+        .join("|");
+
+        format!("{:016x}", Self::fnv1a(content.as_bytes()))
+    }
+
+    /// 64 bit FNV-1a hash
+    pub fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+        })
+    }
+
+    /// Ids for a whole grouping of notices. The same firm can file more than
+    /// once for the same dates (i.e. separate sites), so repeats are given an
+    /// occurrence suffix to keep every id unique. Repeats are numbered by
+    /// their filing and content rather than scrape order, so a notice keeps
+    /// its id when the state reorders its listing.
+    pub fn unique_ids(notices: &[WARNNotice]) -> Vec<String> {
+        Self::with_occurrences(notices, Self::stable_id, |notice| {
+            format!(
+                "{}|{}",
+                Self::filing_id(notice),
+                Self::content_fingerprint(notice)
+            )
+        })
+    }
+
+    /// Filing ids for a whole grouping of notices. A firm can file twice for
+    /// the same site on the same day (i.e. separate layoffs), so repeats are
+    /// given an occurrence suffix numbered by their content.
+    pub fn unique_filing_ids(notices: &[WARNNotice]) -> Vec<String> {
+        Self::with_occurrences(notices, Self::filing_id, Self::content_fingerprint)
+    }
+
+    /// # Params
+    /// notices: The notices to give ids
+    /// id: The (possibly repeated) id of a notice
+    /// rank: Orders notices sharing an id. Notices that tie are identical to
+    /// the id's consumer, so which of them gets which suffix doesn't matter
+    ///
+    /// # Return
+    /// The ids of the notices, in their order, with repeats suffixed by their
+    /// occurrence within the ranked order
+    fn with_occurrences(
+        notices: &[WARNNotice],
+        id: fn(&WARNNotice) -> String,
+        rank: impl Fn(&WARNNotice) -> String,
+    ) -> Vec<String> {
+        let keys: Vec<(String, String)> = notices
+            .iter()
+            .map(|notice| (id(notice), rank(notice)))
+            .collect();
+        let mut ranked: Vec<usize> = (0..notices.len()).collect();
+        ranked.sort_by(|left, right| keys[*left].cmp(&keys[*right]));
+
+        let mut ids = vec![String::new(); notices.len()];
+        let mut occurrence = 0;
+        for (position, idx) in ranked.iter().enumerate() {
+            let (notice_id, _) = &keys[*idx];
+            let repeats_previous = position > 0 && keys[ranked[position - 1]].0 == *notice_id;
+            occurrence = if repeats_previous { occurrence + 1 } else { 1 };
+            ids[*idx] = match occurrence {
+                1 => notice_id.clone(),
+                occurrence => format!("{}-{}", notice_id, occurrence),
+            };
+        }
+        ids
+    }
+}
@@ -0,0 +1,172 @@
+//! Aggregate statistics over a grouping of notices
+use std::collections::BTreeMap;
+
+use crate::{
+    employee_counts::EmployeeCounts,
+    location_parser::LocationParser,
+    notice_dates::NoticeDates,
+    notices::{NoticeTotals, WARNNotice, WARNNotices, WARNStatistics},
+};
+
+/// Key used for notices whose field could not be understood
+pub const UNKNOWN_KEY: &str = "Unknown";
+
+/// Running totals for a single key
+#[derive(Clone, Copy, Default)]
+struct Totals {
+    notice_count: u32,
+    affected_employees: u32,
+}
+
+impl Totals {
+    fn add(&mut self, notice: &WARNNotice) {
+        self.notice_count += 1;
+        self.affected_employees += EmployeeCounts::parse(notice.affected_employees()).unwrap_or(0);
+    }
+
+    fn to_proto(self, key: String) -> NoticeTotals {
+        let mut totals = NoticeTotals::new();
+        totals.set_key(key);
+        totals.set_notice_count(self.notice_count);
+        totals.set_affected_employees(self.affected_employees);
+        totals
+    }
+}
+
+pub struct NoticeStatistics {}
+
+impl NoticeStatistics {
+    /// # Params
+    /// * notices - The notices to compute statistics over
+    /// * top_employer_count - How many employers to include in the top
+    ///   employers, ranked by affected employees
+    pub fn compute(notices: &WARNNotices, top_employer_count: usize) -> WARNStatistics {
+        let mut overall = Totals::default();
+        notices
+            .notices
+            .iter()
+            .for_each(|notice| overall.add(notice));
+
+        let mut statistics = WARNStatistics::new();
+        statistics.set_notice_count(overall.notice_count);
+        statistics.set_affected_employees(overall.affected_employees);
+        statistics.by_month_received = Self::chronological(Self::totals_by(notices, |notice| {
+            vec![Self::month_key(notice.date_received())]
+        }));
+        statistics.by_effective_month = Self::chronological(Self::totals_by(notices, |notice| {
+            vec![Self::month_key(notice.effective_date())]
+        }));
+        statistics.by_town = Self::largest_first(Self::totals_by(notices, Self::town_keys));
+        statistics.top_employers = Self::largest_first(Self::totals_by_employer(notices))
+            .into_iter()
+            .take(top_employer_count)
+            .collect();
+
+        statistics
+    }
+
+    /// Totals each notice under every key returned for it
+    fn totals_by<F>(notices: &WARNNotices, mut keys_for: F) -> BTreeMap<String, Totals>
+    where
+        F: FnMut(&WARNNotice) -> Vec<String>,
+    {
+        let mut totals: BTreeMap<String, Totals> = BTreeMap::new();
+        for notice in notices.notices.iter() {
+            for key in keys_for(notice) {
+                totals.entry(key).or_default().add(notice);
+            }
+        }
+        totals
+    }
+
+    /// Employers are matched by the id of the company notices were resolved
+    /// to, else by firm name ignoring case and surrounding whitespace. Two
+    /// companies sharing a name are kept apart. Each employer is reported
+    /// under the first name seen for it.
+    fn totals_by_employer(notices: &WARNNotices) -> Vec<(String, Totals)> {
+        let mut employers: BTreeMap<String, (String, Totals)> = BTreeMap::new();
+        for notice in notices.notices.iter() {
+            let (employer, name) = Self::employer(notice);
+            employers
+                .entry(employer)
+                .or_insert_with(|| (name, Totals::default()))
+                .1
+                .add(notice);
+        }
+        employers.into_values().collect()
+    }
+
+    /// # Return
+    /// What tells the notice's employer apart, and the name it is reported
+    /// under
+    fn employer(notice: &WARNNotice) -> (String, String) {
+        let firm_name = notice.firm_name().trim();
+        if notice.has_company_id() {
+            let name = match notice.company_name().trim() {
+                "" => firm_name,
+                company_name => company_name,
+            };
+            return (format!("id:{}", notice.company_id()), name.to_string());
+        }
+
+        match firm_name.is_empty() {
+            true => (UNKNOWN_KEY.to_string(), UNKNOWN_KEY.to_string()),
+            false => (
+                format!("name:{}", firm_name.to_lowercase()),
+                firm_name.to_string(),
+            ),
+        }
+    }
+
+    /// Month keys (YYYY-MM) sort chronologically, with Unknown last
+    fn chronological(totals: BTreeMap<String, Totals>) -> Vec<NoticeTotals> {
+        totals
+            .into_iter()
+            .map(|(key, totals)| totals.to_proto(key))
+            .collect()
+    }
+
+    /// Most affected employees first. Ties are broken by notice count, then key.
+    fn largest_first(totals: impl IntoIterator<Item = (String, Totals)>) -> Vec<NoticeTotals> {
+        let mut ordered = totals.into_iter().collect::<Vec<(String, Totals)>>();
+        ordered.sort_by(|(left_key, left), (right_key, right)| {
+            right
+                .affected_employees
+                .cmp(&left.affected_employees)
+                .then(right.notice_count.cmp(&left.notice_count))
+                .then(left_key.cmp(right_key))
+        });
+        ordered
+            .into_iter()
+            .map(|(key, totals)| totals.to_proto(key))
+            .collect()
+    }
+
+    fn month_key(raw_date: &str) -> String {
+        NoticeDates::parse_month(raw_date)
+            .map(|month| month.format("%Y-%m").to_string())
+            .unwrap_or_else(|| UNKNOWN_KEY.to_string())
+    }
+
+    /// A notice can list several sites. It counts towards each of them.
+    /// Sites that are not a recognised town, including those outside
+    /// Massachusetts, are keyed by their text.
+    fn town_keys(notice: &WARNNotice) -> Vec<String> {
+        let mut towns = LocationParser::locations(notice)
+            .iter()
+            .map(|location| match location.has_town() {
+                true => location.town().to_string(),
+                false => location.raw().to_string(),
+            })
+            .filter(|town| !town.is_empty())
+            .collect::<Vec<String>>();
+        // A notice naming two neighbourhoods of one town counts once for it
+        towns.sort();
+        towns.dedup();
+
+        match towns.is_empty() {
+            true => vec![UNKNOWN_KEY.to_string()],
+            false => towns,
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Keeps every notice scraped on disk, one file per fiscal year. States only
+//! publish the current year's notices, so without it a company's earlier
+//! notices are lost once the year rolls over.
+//!
+//! A notice's fiscal year is the year of the year to date report it is
+//! listed in, which is the calendar year it was received.
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use chrono::Datelike;
+use protobuf::Message;
+
+use crate::{
+    notice_dates::NoticeDates,
+    notice_id::NoticeId,
+    notices::{WARNNotice, WARNNotices},
+};
+
+/// Fiscal year of notices with neither a received nor an effective date
+const UNKNOWN_YEAR: &str = "unknown";
+const STORE_FILE_PREFIX: &str = "notices_";
+const STORE_FILE_EXTENSION: &str = "pb";
+
+pub struct NoticeStore {
+    directory: PathBuf,
+}
+
+impl NoticeStore {
+    /// # Params
+    /// * directory - Where the yearly files are kept. It is created on the
+    ///   first save.
+    pub fn new(directory: &Path) -> NoticeStore {
+        NoticeStore {
+            directory: directory.to_path_buf(),
+        }
+    }
+
+    /// # Brief
+    /// Adds the notices to the file of their fiscal year. A stored notice of
+    /// the same filing (firm, locations, received date and state) is
+    /// replaced, so re-scraping a year updates its notices rather than
+    /// duplicating them, even once an effective date is rescheduled. A firm
+    /// filing twice for the same site on the same day has both notices kept.
+    /// Years without new or replaced notices are left untouched.
+    pub fn save(&self, notices: &WARNNotices) -> io::Result<()> {
+        // Notices are None once replaced by one of another fiscal year
+        let mut years: BTreeMap<String, Vec<Option<WARNNotice>>> = BTreeMap::new();
+        let mut stored_notices: Vec<WARNNotice> = Vec::new();
+        let mut stored_locations: Vec<(String, usize)> = Vec::new();
+        for year in self.fiscal_years()? {
+            let stored = Self::read_year(&self.year_path(&year))?.notices;
+            stored_locations.extend((0..stored.len()).map(|idx| (year.clone(), idx)));
+            stored_notices.extend(stored.iter().cloned());
+            years.insert(year, stored.into_iter().map(Some).collect());
+        }
+        let mut stored_at: HashMap<String, (String, usize)> =
+            NoticeId::unique_filing_ids(&stored_notices)
+                .into_iter()
+                .zip(stored_locations)
+                .collect();
+
+        let mut changed_years: BTreeSet<String> = BTreeSet::new();
+        let filing_ids = NoticeId::unique_filing_ids(&notices.notices);
+        for (notice, filing_id) in notices.notices.iter().zip(filing_ids) {
+            let year = Self::fiscal_year(notice);
+            match stored_at.get(&filing_id) {
+                Some((stored_year, idx)) if *stored_year == year => {
+                    years.get_mut(&year).unwrap()[*idx] = Some(notice.clone());
+                }
+                stored => {
+                    // Without a received date, a rescheduled notice can
+                    // change fiscal year
+                    if let Some((stored_year, idx)) = stored {
+                        years.get_mut(stored_year).unwrap()[*idx] = None;
+                        changed_years.insert(stored_year.clone());
+                    }
+                    let year_notices = years.entry(year.clone()).or_default();
+                    year_notices.push(Some(notice.clone()));
+                    stored_at.insert(filing_id, (year.clone(), year_notices.len() - 1));
+                }
+            }
+            changed_years.insert(year);
+        }
+
+        fs::create_dir_all(&self.directory)?;
+        for year in changed_years {
+            let mut stored = WARNNotices::new();
+            stored.notices = years.remove(&year).unwrap().into_iter().flatten().collect();
+            self.write_year(&year, &stored)?;
+        }
+
+        Ok(())
+    }
+
+    /// # Return
+    /// Every stored notice, oldest fiscal year first. A store that was never
+    /// saved to is empty.
+    pub fn load(&self) -> io::Result<WARNNotices> {
+        let mut notices = WARNNotices::new();
+        for year in self.fiscal_years()? {
+            notices
+                .notices
+                .append(&mut Self::read_year(&self.year_path(&year))?.notices);
+        }
+        Ok(notices)
+    }
+
+    /// # Return
+    /// The fiscal years stored, in order
+    pub fn fiscal_years(&self) -> io::Result<Vec<String>> {
+        if !self.directory.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut years = fs::read_dir(&self.directory)?
+            .filter_map(|entry| {
+                let path = entry.ok()?.path();
+                if path.extension()? != STORE_FILE_EXTENSION {
+                    return None;
+                }
+                let file_stem = path.file_stem()?.to_str()?;
+                file_stem
+                    .strip_prefix(STORE_FILE_PREFIX)
+                    .map(str::to_string)
+            })
+            .collect::<Vec<String>>();
+        // Unknown sorts after every year
+        years.sort();
+        Ok(years)
+    }
+
+    /// The year the notice was received, else the year it takes effect
+    fn fiscal_year(notice: &WARNNotice) -> String {
+        NoticeDates::parse(notice.date_received())
+            .or_else(|| NoticeDates::parse(notice.effective_date()))
+            .map(|date| date.year().to_string())
+            .unwrap_or_else(|| UNKNOWN_YEAR.to_string())
+    }
+
+    fn year_path(&self, year: &str) -> PathBuf {
+        self.directory.join(format!(
+            "{}{}.{}",
+            STORE_FILE_PREFIX, year, STORE_FILE_EXTENSION
+        ))
+    }
+
+    fn write_year(&self, year: &str, notices: &WARNNotices) -> io::Result<()> {
+        let path = self.year_path(year);
+        if notices.notices.is_empty() {
+            // Nothing to remove if the year was never written
+            return match fs::remove_file(path) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            };
+        }
+
+        // Written aside then moved, so an interrupted save can't lose a year
+        let partial_path = path.with_extension(format!("{}.partial", STORE_FILE_EXTENSION));
+        let bytes = notices
+            .write_to_bytes()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        fs::write(&partial_path, bytes)?;
+        fs::rename(&partial_path, &path)
+    }
+
+    fn read_year(path: &Path) -> io::Result<WARNNotices> {
+        WARNNotices::parse_from_bytes(&fs::read(path)?).map_err(|err| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: {}", path.display(), err),
+            )
+        })
+    }
+}
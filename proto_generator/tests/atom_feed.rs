@@ -0,0 +1,112 @@
+use chrono::{DateTime, TimeZone, Utc};
+use proto_generator::{
+    exporters::atom_feed::FeedState,
+    notice_id::NoticeId,
+    notices::{WARNNotice, WARNNotices},
+};
+
+fn notice(firm_name: &str, date_received: &str, affected_employees: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_date_received(date_received.to_string());
+    notice.set_effective_date("2023-11-01".to_string());
+    notice.set_affected_employees(affected_employees.to_string());
+    notice
+}
+
+fn notices(notice_vec: Vec<WARNNotice>) -> WARNNotices {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    notices
+}
+
+fn timestamp(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+}
+
+fn updated(state: &FeedState, notice: &WARNNotice) -> DateTime<Utc> {
+    state.get(&NoticeId::stable_id(notice)).unwrap().updated
+}
+
+#[test]
+fn new_entries_are_stamped_with_the_date_received() {
+    let received = notice("Acme Widgets", "2023-09-01", "40");
+    let not_received = notice("Bay State Bakery", "", "7");
+    let mut state = FeedState::default();
+
+    state.refresh(
+        &notices(vec![received.clone(), not_received.clone()]),
+        timestamp(2023, 10, 1),
+    );
+
+    assert_eq!(updated(&state, &received), timestamp(2023, 9, 1));
+    assert_eq!(updated(&state, &not_received), timestamp(2023, 10, 1));
+}
+
+#[test]
+fn only_changed_entries_are_stamped_again() {
+    let unchanged = notice("Acme Widgets", "2023-09-01", "40");
+    let original = notice("Bay State Bakery", "2023-09-05", "7");
+    let mut state = FeedState::default();
+    state.refresh(
+        &notices(vec![unchanged.clone(), original.clone()]),
+        timestamp(2023, 10, 1),
+    );
+
+    let mut changed = original.clone();
+    changed.set_affected_employees("12".to_string());
+    state.refresh(
+        &notices(vec![unchanged.clone(), changed.clone()]),
+        timestamp(2023, 10, 8),
+    );
+
+    assert_eq!(updated(&state, &unchanged), timestamp(2023, 9, 1));
+    assert_eq!(updated(&state, &changed), timestamp(2023, 10, 8));
+}
+
+#[test]
+fn state_survives_a_save_and_load() {
+    let state_path = std::env::temp_dir().join("warn_scraper_feed_state.tsv");
+    let original = notice("Acme Widgets", "2023-09-01", "40");
+    let mut state = FeedState::default();
+    state.refresh(&notices(vec![original.clone()]), timestamp(2023, 10, 1));
+    state.save(&state_path).unwrap();
+
+    let mut loaded = FeedState::load(&state_path).unwrap();
+    std::fs::remove_file(&state_path).unwrap();
+    let id = NoticeId::stable_id(&original);
+    assert_eq!(loaded.get(&id), state.get(&id));
+
+    // The loaded state still knows the entry, so it is not stamped again
+    loaded.refresh(&notices(vec![original.clone()]), timestamp(2023, 10, 8));
+    assert_eq!(updated(&loaded, &original), timestamp(2023, 9, 1));
+}
+
+#[test]
+fn a_missing_state_file_is_empty() {
+    let state_path = std::env::temp_dir().join("warn_scraper_missing_feed_state.tsv");
+    let _ = std::fs::remove_file(&state_path);
+
+    let state = FeedState::load(&state_path).unwrap();
+
+    assert_eq!(state.get("anything"), None);
+}
+
+#[test]
+fn entries_missing_from_the_feed_expire() {
+    let kept = notice("Acme Widgets", "2023-09-01", "40");
+    let dropped = notice("Bay State Bakery", "2023-09-05", "7");
+    let mut state = FeedState::default();
+    state.refresh(
+        &notices(vec![kept.clone(), dropped.clone()]),
+        timestamp(2023, 10, 1),
+    );
+
+    // Still remembered a while after leaving the feed
+    state.refresh(&notices(vec![kept.clone()]), timestamp(2024, 6, 1));
+    assert!(state.get(&NoticeId::stable_id(&dropped)).is_some());
+
+    state.refresh(&notices(vec![kept.clone()]), timestamp(2025, 1, 1));
+    assert!(state.get(&NoticeId::stable_id(&dropped)).is_none());
+    assert!(state.get(&NoticeId::stable_id(&kept)).is_some());
+}
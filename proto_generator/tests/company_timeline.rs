@@ -0,0 +1,210 @@
+use proto_generator::{
+    company_resolver::CompanyResolver,
+    company_timeline::CompanyTimelineBuilder,
+    notice_store::NoticeStore,
+    notices::{WARNNotice, WARNNotices},
+};
+
+fn notice(firm_name: &str, date_received: &str, affected_employees: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_date_received(date_received.to_string());
+    notice.set_affected_employees(affected_employees.to_string());
+    notice
+}
+
+fn notices(notice_vec: Vec<WARNNotice>) -> WARNNotices {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    notices
+}
+
+#[test]
+fn store_keeps_every_fiscal_year_without_duplicating_notices() {
+    let store_directory = std::env::temp_dir().join("warn_scraper_notice_store");
+    let _ = std::fs::remove_dir_all(&store_directory);
+    let store = NoticeStore::new(&store_directory);
+
+    store
+        .save(&notices(vec![
+            notice("ACME, Inc.", "2023-03-15", "40"),
+            notice("Bay State Bakery", "2023-07-01", "7"),
+        ]))
+        .unwrap();
+    let mut updated = notice("ACME, Inc.", "2023-03-15", "45");
+    updated.set_reason_for_dislocation("Plant closing".to_string());
+    store
+        .save(&notices(vec![
+            updated,
+            notice("Acme Inc", "02/20/2024", "25"),
+        ]))
+        .unwrap();
+
+    let stored = store.load().unwrap();
+    assert_eq!(store.fiscal_years().unwrap(), vec!["2023", "2024"]);
+    assert_eq!(stored.notices.len(), 3);
+    assert_eq!(stored.notices[0].affected_employees(), "45");
+    assert_eq!(stored.notices[0].reason_for_dislocation(), "Plant closing");
+    assert_eq!(stored.notices[2].firm_name(), "Acme Inc");
+
+    std::fs::remove_dir_all(&store_directory).unwrap();
+}
+
+fn empty_store(name: &str) -> (NoticeStore, std::path::PathBuf) {
+    let store_directory = std::env::temp_dir().join(name);
+    let _ = std::fs::remove_dir_all(&store_directory);
+    (NoticeStore::new(&store_directory), store_directory)
+}
+
+#[test]
+fn store_replaces_a_notice_whose_effective_date_was_rescheduled() {
+    let (store, store_directory) = empty_store("warn_scraper_rescheduled_store");
+    let mut scheduled = notice("Acme Inc", "2023-03-15", "100");
+    scheduled.set_effective_date("2023-05-15".to_string());
+    let mut rescheduled = scheduled.clone();
+    rescheduled.set_effective_date("2023-06-30".to_string());
+
+    store.save(&notices(vec![scheduled])).unwrap();
+    store.save(&notices(vec![rescheduled])).unwrap();
+
+    let stored = store.load().unwrap();
+    std::fs::remove_dir_all(&store_directory).unwrap();
+    assert_eq!(stored.notices.len(), 1);
+    assert_eq!(stored.notices[0].effective_date(), "2023-06-30");
+    let timelines = CompanyTimelineBuilder::build(&stored);
+    let entries = &timelines.timelines[0].entries;
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].cumulative_affected_employees(), 100);
+}
+
+#[test]
+fn store_keeps_each_site_of_a_firm_apart() {
+    let (store, store_directory) = empty_store("warn_scraper_sites_store");
+    let site = |firm_locations: &str, affected_employees: &str| {
+        let mut site = notice("Acme Inc", "2023-03-15", affected_employees);
+        site.set_firm_locations(firm_locations.to_string());
+        site.set_effective_date("2023-05-15".to_string());
+        site
+    };
+
+    store
+        .save(&notices(vec![
+            site("Worcester", "60"),
+            site("Lowell", "40"),
+        ]))
+        .unwrap();
+    // A later scrape only listing the second site
+    store.save(&notices(vec![site("Lowell", "45")])).unwrap();
+
+    let stored = store.load().unwrap();
+    std::fs::remove_dir_all(&store_directory).unwrap();
+    assert_eq!(
+        stored
+            .notices
+            .iter()
+            .map(|notice| (notice.firm_locations(), notice.affected_employees()))
+            .collect::<Vec<_>>(),
+        vec![("Worcester", "60"), ("Lowell", "45")]
+    );
+}
+
+#[test]
+fn store_keeps_identical_filings_of_a_firm_apart() {
+    let (store, store_directory) = empty_store("warn_scraper_repeated_filings_store");
+    // Separate layoffs filed for the same site on the same day
+    let layoffs = notices(vec![
+        notice("Acme Inc", "2023-03-15", "60"),
+        notice("Acme Inc", "2023-03-15", "40"),
+    ]);
+
+    store.save(&layoffs).unwrap();
+    // Scraping the same listing again replaces them rather than adding more
+    store.save(&layoffs).unwrap();
+
+    let stored = store.load().unwrap();
+    std::fs::remove_dir_all(&store_directory).unwrap();
+    assert_eq!(
+        stored
+            .notices
+            .iter()
+            .map(|notice| notice.affected_employees())
+            .collect::<Vec<_>>(),
+        vec!["60", "40"]
+    );
+}
+
+#[test]
+fn store_moves_a_notice_rescheduled_into_another_fiscal_year() {
+    let (store, store_directory) = empty_store("warn_scraper_moved_store");
+    // Without a received date, the fiscal year is the effective date's
+    let scheduled = notice("Acme Inc", "", "100");
+    let mut rescheduled = scheduled.clone();
+    rescheduled.set_effective_date("2024-01-15".to_string());
+    let mut scheduled = scheduled;
+    scheduled.set_effective_date("2023-12-15".to_string());
+
+    store.save(&notices(vec![scheduled])).unwrap();
+    store.save(&notices(vec![rescheduled])).unwrap();
+
+    let stored = store.load().unwrap();
+    let fiscal_years = store.fiscal_years().unwrap();
+    std::fs::remove_dir_all(&store_directory).unwrap();
+    assert_eq!(fiscal_years, vec!["2024"]);
+    assert_eq!(stored.notices.len(), 1);
+    assert_eq!(stored.notices[0].effective_date(), "2024-01-15");
+}
+
+#[test]
+fn timeline_orders_notices_with_running_totals() {
+    let mut company_notices = notices(vec![
+        notice("Acme Incorporated d/b/a Acme Labs", "02/20/2024", "25"),
+        notice("ACME, Inc.", "2023-03-15", "40"),
+        notice("Acme Inc", "", "10"),
+        notice("Acme Inc", "November 1, 2023", "1,200"),
+    ]);
+    CompanyResolver::default().resolve(&mut company_notices);
+
+    let timelines = CompanyTimelineBuilder::build(&company_notices);
+
+    assert_eq!(timelines.timelines.len(), 1);
+    let timeline = &timelines.timelines[0];
+    assert_eq!(timeline.company_name(), "Acme Inc");
+    assert_eq!(timeline.company_id(), CompanyResolver::company_id("acme"));
+    assert_eq!(
+        timeline
+            .entries
+            .iter()
+            .map(|entry| (
+                entry.notice.date_received(),
+                entry.cumulative_notice_count(),
+                entry.cumulative_affected_employees()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("2023-03-15", 1, 40),
+            ("November 1, 2023", 2, 1240),
+            ("02/20/2024", 3, 1265),
+            ("", 4, 1275),
+        ]
+    );
+}
+
+#[test]
+fn timeline_is_per_company() {
+    let company_notices = notices(vec![
+        notice("Bay State Bakery", "2023-07-01", "7"),
+        notice("Acme Inc", "2023-03-15", "40"),
+        notice("Bay State Bakery LLC", "2024-01-05", "3"),
+    ]);
+
+    let timelines = CompanyTimelineBuilder::build(&company_notices);
+
+    assert_eq!(
+        timelines
+            .timelines
+            .iter()
+            .map(|timeline| (timeline.company_name(), timeline.entries.len()))
+            .collect::<Vec<_>>(),
+        vec![("Acme Inc", 1), ("Bay State Bakery", 2)]
+    );
+}
@@ -0,0 +1,46 @@
+use proto_generator::{
+    gazetteer::{Gazetteer, GazetteerEntry},
+    notices::NoticeLocation,
+};
+
+#[test]
+fn looks_up_towns_ignoring_case() {
+    let expected = GazetteerEntry {
+        town: "Worcester".to_string(),
+        county: "Worcester".to_string(),
+        region: "Central".to_string(),
+        latitude: 42.26,
+        longitude: -71.80,
+    };
+
+    assert_eq!(Gazetteer::lookup("Worcester"), Some(&expected));
+    assert_eq!(Gazetteer::lookup("WORCESTER"), Some(&expected));
+    assert_eq!(Gazetteer::lookup("Dorchester"), None);
+}
+
+#[test]
+fn geocodes_locations_with_a_known_town() {
+    let mut location = NoticeLocation::new();
+    location.set_town("Lowell".to_string());
+
+    Gazetteer::geocode(&mut location);
+
+    assert_eq!(location.county(), "Middlesex");
+    assert_eq!(location.region(), "Greater Boston");
+    assert_eq!(location.latitude(), 42.64);
+    assert_eq!(location.longitude(), -71.32);
+}
+
+#[test]
+fn leaves_unknown_towns_untouched() {
+    let mut location = NoticeLocation::new();
+    location.set_town("Atlantis".to_string());
+
+    Gazetteer::geocode(&mut location);
+
+    assert_eq!(location, {
+        let mut unknown = NoticeLocation::new();
+        unknown.set_town("Atlantis".to_string());
+        unknown
+    });
+}
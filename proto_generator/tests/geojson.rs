@@ -0,0 +1,85 @@
+use proto_generator::{
+    exporters::{geojson::GeoJsonExporter, NoticeExporter},
+    notices::{NoticeLocation, WARNNotice, WARNNotices},
+};
+
+fn export(notice_vec: Vec<WARNNotice>) -> String {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    GeoJsonExporter::new().export(&notices)
+}
+
+fn feature_lines(geojson: &str) -> Vec<&str> {
+    geojson
+        .lines()
+        .filter(|line| line.starts_with("{\"type\":\"Feature\""))
+        .collect()
+}
+
+#[test]
+fn exports_a_point_for_each_geocoded_site() {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name("Acme Widgets".to_string());
+    notice.set_firm_locations("Dorchester and Lowell, Statewide".to_string());
+    notice.set_affected_employees("1,200".to_string());
+
+    let geojson = export(vec![notice]);
+    let features = feature_lines(&geojson);
+
+    assert!(geojson.starts_with("{\"type\":\"FeatureCollection\",\"features\":[\n"));
+    assert_eq!(features.len(), 2);
+    assert!(
+        features[0].contains("\"geometry\":{\"type\":\"Point\",\"coordinates\":[-71.06,42.36]}")
+    );
+    assert!(features[0].contains("\"town\":\"Boston\",\"neighbourhood\":\"Dorchester\""));
+    assert!(features[0].contains("\"affected_employees\":1200"));
+    assert!(features[1].contains("\"town\":\"Lowell\""));
+}
+
+#[test]
+fn escapes_text_properties() {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name("\"Quoted\" \\ Co.\nLLC".to_string());
+    notice.set_firm_locations("Boston".to_string());
+
+    let features = export(vec![notice]);
+
+    assert!(features.contains("\"firm_name\":\"\\\"Quoted\\\" \\\\ Co.\\nLLC\""));
+    assert_eq!(feature_lines(&features).len(), 1);
+}
+
+#[test]
+fn missing_properties_are_null() {
+    let mut location = NoticeLocation::new();
+    location.set_town("Boston".to_string());
+    location.set_latitude(42.36);
+    location.set_longitude(-71.06);
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name("Acme Widgets".to_string());
+    notice.locations.push(location);
+
+    let geojson = export(vec![notice]);
+
+    assert!(geojson.contains("\"neighbourhood\":null"));
+    assert!(geojson.contains("\"county\":null"));
+    assert!(geojson.contains("\"effective_date\":null"));
+    assert!(geojson.contains("\"affected_employees\":null"));
+    assert!(!geojson.contains("\"\""));
+}
+
+#[test]
+fn sites_outside_massachusetts_are_not_geocoded() {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name("Acme Widgets".to_string());
+    notice.set_firm_locations("1200 Industrial Pkwy, Suite 4, Rochester, NY 14624".to_string());
+    notice.set_state("NY".to_string());
+
+    let geojson = export(vec![notice]);
+    let features = feature_lines(&geojson);
+
+    assert_eq!(features.len(), 1);
+    assert!(features[0].contains("\"geometry\":null"));
+    assert!(features[0].contains("\"town\":null"));
+    assert!(features[0].contains("\"county\":null"));
+    assert!(!geojson.contains("Plymouth"));
+}
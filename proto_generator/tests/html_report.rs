@@ -0,0 +1,110 @@
+use chrono::{TimeZone, Utc};
+use proto_generator::{
+    exporters::{html_report::HtmlReportExporter, NoticeExporter},
+    notices::{WARNNotice, WARNNotices},
+};
+
+fn notice(firm_name: &str, date_received: &str, affected_employees: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_firm_locations("Boston".to_string());
+    notice.set_date_received(date_received.to_string());
+    notice.set_affected_employees(affected_employees.to_string());
+    notice
+}
+
+fn notices(notice_vec: Vec<WARNNotice>) -> WARNNotices {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    notices
+}
+
+fn report(template: &str, weekly: Vec<WARNNotice>, all: Vec<WARNNotice>) -> String {
+    HtmlReportExporter::new(
+        "WARN <Report>".to_string(),
+        Utc.with_ymd_and_hms(2023, 10, 1, 12, 30, 0).unwrap(),
+        notices(weekly),
+        Some(template.to_string()),
+    )
+    .export(&notices(all))
+}
+
+#[test]
+fn firm_names_are_escaped() {
+    let rendered = report(
+        "{{all_notices_table}}",
+        vec![],
+        vec![notice("Smith & Sons <Holdings>", "2023-09-01", "40")],
+    );
+
+    assert!(rendered.contains("<td>Smith &amp; Sons &lt;Holdings&gt;</td>"));
+    assert!(!rendered.contains("<Holdings>"));
+}
+
+#[test]
+fn monthly_totals_group_by_month_received() {
+    let rendered = report(
+        "{{monthly_totals_table}}",
+        vec![],
+        vec![
+            notice("Acme Widgets", "2023-09-01", "40"),
+            notice("Bay State Bakery", "09/20/2023", "Approximately 1,200"),
+            notice("Cape Cod Crafts", "2023-08-15", "7"),
+            notice("Dedham Dairy", "", "3"),
+        ],
+    );
+
+    let august = rendered.find("August 2023").unwrap();
+    let september = rendered.find("September 2023").unwrap();
+    let unknown = rendered.find("Unknown").unwrap();
+    assert!(august < september && september < unknown);
+    assert!(rendered
+        .contains("<tr><td data-sort=\"2023-09\">September 2023</td><td>2</td><td>1240</td></tr>"));
+    assert!(rendered
+        .contains("<tr><td data-sort=\"2023-08\">August 2023</td><td>1</td><td>7</td></tr>"));
+    assert!(rendered.contains("<tr><td data-sort=\"9999\">Unknown</td><td>1</td><td>3</td></tr>"));
+}
+
+#[test]
+fn template_placeholders_are_filled() {
+    let rendered = report(
+        "{{title}}|{{ generated_at }}|{{notice_count}}|{{weekly_notice_count}}|\
+         {{total_affected_employees}}|{{unknown}}|{{unclosed",
+        vec![notice("Acme Widgets", "2023-09-28", "40")],
+        vec![
+            notice("Acme Widgets", "2023-09-28", "40"),
+            notice("Bay State Bakery", "2023-09-01", "7"),
+        ],
+    );
+
+    assert_eq!(
+        rendered,
+        "WARN &lt;Report&gt;|October 1, 2023 12:30 UTC|2|1|47|{{unknown}}|{{unclosed"
+    );
+}
+
+#[test]
+fn notice_text_is_not_treated_as_a_placeholder() {
+    let rendered = report(
+        "{{all_notices_table}}",
+        vec![],
+        vec![notice("{{title}} Inc", "2023-09-01", "40")],
+    );
+
+    assert!(rendered.contains("<td>{{title}} Inc</td>"));
+    assert!(!rendered.contains("WARN &lt;Report&gt;"));
+}
+
+#[test]
+fn default_template_has_no_placeholders_left() {
+    let rendered = HtmlReportExporter::new(
+        "WARN Notices".to_string(),
+        Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap(),
+        notices(vec![]),
+        None,
+    )
+    .export(&notices(vec![notice("Acme Widgets", "2023-09-01", "40")]));
+
+    assert!(!rendered.contains("{{"));
+    assert!(rendered.contains("<td>Acme Widgets</td>"));
+}
@@ -0,0 +1,79 @@
+use chrono::{TimeZone, Utc};
+use proto_generator::{
+    exporters::{icalendar::ICalendarExporter, NoticeExporter},
+    notices::{WARNNotice, WARNNotices},
+};
+
+fn notice(firm_name: &str, firm_locations: &str, effective_date: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_firm_locations(firm_locations.to_string());
+    notice.set_date_received("2023-09-01".to_string());
+    notice.set_effective_date(effective_date.to_string());
+    notice
+}
+
+fn export(notice_vec: Vec<WARNNotice>) -> String {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    ICalendarExporter::new(
+        "WARN Notices".to_string(),
+        Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap(),
+    )
+    .export(&notices)
+}
+
+fn uids(calendar: &str) -> Vec<String> {
+    calendar
+        .lines()
+        .filter_map(|line| line.strip_prefix("UID:"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn uid_survives_a_rescheduled_effective_date() {
+    let scheduled = export(vec![notice("Acme Widgets", "Worcester", "2023-11-01")]);
+    let rescheduled = export(vec![notice("Acme Widgets", "Worcester", "2023-12-15")]);
+
+    assert!(rescheduled.contains("DTSTART;VALUE=DATE:20231215\r\n"));
+    assert_eq!(uids(&scheduled).len(), 1);
+    assert_eq!(uids(&scheduled), uids(&rescheduled));
+}
+
+#[test]
+fn uid_does_not_depend_on_the_other_notices() {
+    let worcester = notice("Acme Widgets", "Worcester", "2023-11-01");
+    let lowell = notice("Acme Widgets", "Lowell", "2023-11-01");
+
+    let both = uids(&export(vec![worcester.clone(), lowell.clone()]));
+
+    assert_eq!(both.len(), 2);
+    assert_ne!(both[0], both[1]);
+    assert_eq!(uids(&export(vec![lowell])), vec![both[1].clone()]);
+    assert_eq!(uids(&export(vec![worcester])), vec![both[0].clone()]);
+}
+
+#[test]
+fn notices_without_an_effective_date_are_left_out() {
+    let calendar = export(vec![
+        notice("Acme Widgets", "Worcester", "TBD"),
+        notice("Bay State Bakery", "Lowell", "2023-11-01"),
+    ]);
+
+    assert_eq!(uids(&calendar).len(), 1);
+    assert!(calendar.contains("SUMMARY:WARN layoff: Bay State Bakery\r\n"));
+}
+
+#[test]
+fn identical_filings_get_distinct_uids() {
+    let first = notice("Acme Widgets", "Worcester", "2023-11-01");
+    let second = notice("Acme Widgets", "Worcester", "2024-01-15");
+
+    let in_order = uids(&export(vec![first.clone(), second.clone()]));
+    let reordered = uids(&export(vec![second, first]));
+
+    assert_eq!(in_order.len(), 2);
+    assert_ne!(in_order[0], in_order[1]);
+    assert_eq!(in_order, vec![reordered[1].clone(), reordered[0].clone()]);
+}
@@ -0,0 +1,93 @@
+use proto_generator::{
+    location_parser::LocationParser,
+    notices::{NoticeLocation, WARNNotice, WARNNotices},
+};
+
+fn towns(locations: &[NoticeLocation]) -> Vec<(&str, &str)> {
+    locations
+        .iter()
+        .map(|location| (location.town(), location.neighbourhood()))
+        .collect()
+}
+
+#[test]
+fn splits_every_site_in_the_text() {
+    assert_eq!(
+        towns(&LocationParser::parse("Boston, Cambridge and Worcester")),
+        vec![("Boston", ""), ("Cambridge", ""), ("Worcester", "")]
+    );
+    assert_eq!(
+        towns(&LocationParser::parse(
+            "Lowell; Lawrence / Andover & Quincy"
+        )),
+        vec![
+            ("Lowell", ""),
+            ("Lawrence", ""),
+            ("Andover", ""),
+            ("Quincy", "")
+        ]
+    );
+}
+
+#[test]
+fn resolves_neighbourhoods_to_their_town() {
+    let locations = LocationParser::parse("Dorchester");
+
+    assert_eq!(towns(&locations), vec![("Boston", "Dorchester")]);
+    assert_eq!(locations[0].raw(), "Dorchester");
+    assert_eq!(locations[0].county(), "Suffolk");
+}
+
+#[test]
+fn resolves_alternate_spellings_to_the_official_name() {
+    assert_eq!(
+        towns(&LocationParser::parse("Foxboro")),
+        vec![("Foxborough", "")]
+    );
+}
+
+#[test]
+fn ignores_the_state_zip_code_and_town_prefix() {
+    assert_eq!(
+        towns(&LocationParser::parse(
+            "City of Worcester, MA 01608; Mt. Washington, Mass."
+        )),
+        vec![("Worcester", ""), ("Mount Washington", "")]
+    );
+}
+
+#[test]
+fn drops_street_addresses_when_a_town_was_recognised() {
+    assert_eq!(
+        towns(&LocationParser::parse("100 Main Street, Springfield, MA")),
+        vec![("Springfield", "")]
+    );
+}
+
+#[test]
+fn keeps_unrecognised_sites_without_a_town() {
+    let locations = LocationParser::parse("Statewide");
+
+    assert_eq!(locations.len(), 1);
+    assert_eq!(locations[0].raw(), "Statewide");
+    assert!(!locations[0].has_town());
+    assert!(!locations[0].has_latitude());
+}
+
+#[test]
+fn annotates_only_notices_without_locations() {
+    let mut parsed = WARNNotice::new();
+    parsed.set_firm_locations("Lowell".to_string());
+    let mut already_located = WARNNotice::new();
+    already_located.set_firm_locations("Lowell".to_string());
+    let mut location = NoticeLocation::new();
+    location.set_town("Boston".to_string());
+    already_located.locations.push(location);
+    let mut notices = WARNNotices::new();
+    notices.notices = vec![parsed, already_located];
+
+    LocationParser::annotate_notices(&mut notices);
+
+    assert_eq!(towns(&notices.notices[0].locations), vec![("Lowell", "")]);
+    assert_eq!(towns(&notices.notices[1].locations), vec![("Boston", "")]);
+}
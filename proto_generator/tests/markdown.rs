@@ -0,0 +1,83 @@
+use chrono::{TimeZone, Utc};
+use proto_generator::{
+    exporters::{markdown::MarkdownExporter, NoticeExporter},
+    notices::{WARNNotice, WARNNotices},
+};
+
+fn notice(firm_name: &str, firm_locations: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_firm_locations(firm_locations.to_string());
+    notice.set_affected_employees("40".to_string());
+    notice.set_effective_date("2023-11-01".to_string());
+    notice.set_date_received("2023-09-01".to_string());
+    notice
+}
+
+fn export(title: &str, notice_vec: Vec<WARNNotice>) -> String {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    MarkdownExporter::new(
+        title.to_string(),
+        Utc.with_ymd_and_hms(2023, 10, 1, 0, 0, 0).unwrap(),
+    )
+    .export(&notices)
+}
+
+fn table_rows(markdown: &str) -> Vec<&str> {
+    markdown
+        .lines()
+        .filter(|line| line.starts_with("| ") && !line.starts_with("| Company"))
+        .filter(|line| !line.starts_with("| ---"))
+        .collect()
+}
+
+#[test]
+fn pipes_in_cells_are_escaped() {
+    let markdown = export(
+        "WARN Notices",
+        vec![notice("Acme | Widgets", "Boston|Lowell")],
+    );
+
+    assert_eq!(
+        table_rows(&markdown),
+        vec!["| Acme \\| Widgets | Boston\\|Lowell | 40 | 2023-11-01 | 2023-09-01 |"]
+    );
+}
+
+#[test]
+fn new_lines_in_cells_stay_on_the_row() {
+    let markdown = export(
+        "WARN Notices",
+        vec![notice("Acme Widgets", "Boston\nLowell\r\nWorcester")],
+    );
+
+    assert_eq!(
+        table_rows(&markdown),
+        vec!["| Acme Widgets | Boston Lowell  Worcester | 40 | 2023-11-01 | 2023-09-01 |"]
+    );
+}
+
+#[test]
+fn the_title_stays_on_its_heading() {
+    let markdown = export("WARN\nNotices | MA", vec![]);
+
+    assert!(markdown.starts_with("# WARN Notices \\| MA\n\n* Notices: 0\n"));
+}
+
+#[test]
+fn notices_are_grouped_under_month_headings() {
+    let mut unknown = notice("Bay State Bakery", "Lowell");
+    unknown.set_effective_date("TBD".to_string());
+
+    let markdown = export(
+        "WARN Notices",
+        vec![unknown, notice("Acme Widgets", "Boston")],
+    );
+
+    let november = markdown.find("\n## November 2023 (1 notices)\n").unwrap();
+    let unknown = markdown
+        .find("\n## Unknown Effective Date (1 notices)\n")
+        .unwrap();
+    assert!(november < unknown);
+}
@@ -0,0 +1,179 @@
+use proto_generator::{
+    notice_classifier::NoticeClassifier,
+    notices::{
+        ClassificationConfidence, NewYorkNoticeDetails, NoticeKind, WARNNotice, WARNNotices,
+    },
+};
+
+fn with_reason(reason: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name("Acme Widgets".to_string());
+    notice.set_reason_for_dislocation(reason.to_string());
+    notice
+}
+
+fn classify(notice: &WARNNotice) -> (NoticeKind, ClassificationConfidence, String) {
+    let classification = NoticeClassifier::classify(notice);
+    (
+        classification.kind(),
+        classification.confidence(),
+        classification.basis().to_string(),
+    )
+}
+
+#[test]
+fn trusts_a_stated_notice_type_with_high_confidence() {
+    let mut notice = with_reason("Relocation of operations out of state");
+    notice.set_notice_type("Layoff".to_string());
+
+    assert_eq!(
+        classify(&notice),
+        (
+            NoticeKind::KIND_LAYOFF,
+            ClassificationConfidence::CONFIDENCE_HIGH,
+            "notice_type: Layoff".to_string()
+        )
+    );
+}
+
+#[test]
+fn trusts_the_new_york_classification_with_high_confidence() {
+    let mut details = NewYorkNoticeDetails::new();
+    details.set_classification("Plant Closing".to_string());
+    details.set_reason_stated_for_filing("Economic".to_string());
+    let mut notice = WARNNotice::new();
+    notice.new_york = Some(details).into();
+
+    assert_eq!(
+        classify(&notice),
+        (
+            NoticeKind::KIND_CLOSURE,
+            ClassificationConfidence::CONFIDENCE_HIGH,
+            "classification: Plant Closing".to_string()
+        )
+    );
+}
+
+#[test]
+fn falls_back_to_the_text_when_the_stated_field_names_no_kind() {
+    let mut notice = with_reason("Facility shutdown");
+    notice.set_notice_type("Other".to_string());
+
+    assert_eq!(
+        classify(&notice),
+        (
+            NoticeKind::KIND_CLOSURE,
+            ClassificationConfidence::CONFIDENCE_MEDIUM,
+            "reason_for_dislocation mentions shutdown".to_string()
+        )
+    );
+}
+
+#[test]
+fn a_text_mentioning_one_kind_has_medium_confidence() {
+    assert_eq!(
+        classify(&with_reason("Relocation of operations out of state")),
+        (
+            NoticeKind::KIND_RELOCATION,
+            ClassificationConfidence::CONFIDENCE_MEDIUM,
+            "reason_for_dislocation mentions relocation".to_string()
+        )
+    );
+    assert_eq!(
+        classify(&with_reason("REDUCTION IN FORCE")).0,
+        NoticeKind::KIND_LAYOFF
+    );
+}
+
+#[test]
+fn a_partial_closure_is_a_layoff() {
+    assert_eq!(
+        classify(&with_reason("Partial closure of the Lowell plant")),
+        (
+            NoticeKind::KIND_LAYOFF,
+            ClassificationConfidence::CONFIDENCE_MEDIUM,
+            "reason_for_dislocation mentions partial closure".to_string()
+        )
+    );
+}
+
+#[test]
+fn a_text_mentioning_several_kinds_has_low_confidence() {
+    assert_eq!(
+        classify(&with_reason("Layoffs ahead of the closure of the site")),
+        (
+            NoticeKind::KIND_CLOSURE,
+            ClassificationConfidence::CONFIDENCE_LOW,
+            "reason_for_dislocation mentions closure".to_string()
+        )
+    );
+    assert_eq!(
+        classify(&with_reason("Site closing, relocating to Texas")),
+        (
+            NoticeKind::KIND_RELOCATION,
+            ClassificationConfidence::CONFIDENCE_LOW,
+            "reason_for_dislocation mentions relocating".to_string()
+        )
+    );
+}
+
+#[test]
+fn keywords_only_match_whole_words() {
+    assert_eq!(
+        classify(&with_reason("Enclosed is our closeout inventory list")),
+        (
+            NoticeKind::KIND_UNCLASSIFIED,
+            ClassificationConfidence::CONFIDENCE_NONE,
+            String::new()
+        )
+    );
+}
+
+#[test]
+fn searches_the_additional_fields() {
+    let mut notice = WARNNotice::new();
+    notice
+        .additional_fields
+        .insert("Shutdown".to_string(), "Yes".to_string());
+
+    assert_eq!(
+        classify(&notice),
+        (
+            NoticeKind::KIND_CLOSURE,
+            ClassificationConfidence::CONFIDENCE_MEDIUM,
+            "Shutdown mentions shutdown".to_string()
+        )
+    );
+}
+
+#[test]
+fn a_notice_without_any_text_is_unclassified() {
+    let classification = NoticeClassifier::classify(&WARNNotice::new());
+
+    assert_eq!(classification.kind(), NoticeKind::KIND_UNCLASSIFIED);
+    assert_eq!(
+        classification.confidence(),
+        ClassificationConfidence::CONFIDENCE_NONE
+    );
+    assert!(!classification.has_basis());
+}
+
+#[test]
+fn annotates_only_notices_not_classified_yet() {
+    let mut classified = with_reason("Layoff");
+    NoticeClassifier::annotate_notice(&mut classified);
+    classified.set_reason_for_dislocation("Closure".to_string());
+    let mut notices = WARNNotices::new();
+    notices.notices = vec![classified, with_reason("Relocation")];
+
+    NoticeClassifier::annotate_notices(&mut notices);
+
+    assert_eq!(
+        notices.notices[0].classification.kind(),
+        NoticeKind::KIND_LAYOFF
+    );
+    assert_eq!(
+        notices.notices[1].classification.kind(),
+        NoticeKind::KIND_RELOCATION
+    );
+}
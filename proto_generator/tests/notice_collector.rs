@@ -0,0 +1,137 @@
+use proto_generator::{
+    notice_classifier::NoticeClassifier,
+    notice_collector::NoticeCollector,
+    notices::{ClassificationConfidence, NoticeKind, WARNNotice, WARNNotices},
+};
+
+fn notice(firm_name: &str, notice_type: &str, reason: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    if !notice_type.is_empty() {
+        notice.set_notice_type(notice_type.to_string());
+    }
+    if !reason.is_empty() {
+        notice.set_reason_for_dislocation(reason.to_string());
+    }
+    notice
+}
+
+/// A closure stated outright, a layoff and a closure told by their text, a
+/// notice mentioning two kinds and one mentioning none
+fn classified_notices() -> WARNNotices {
+    let mut notices = WARNNotices::new();
+    notices.notices = vec![
+        notice("Acme Widgets", "Closure", ""),
+        notice("Bay State Bakery", "", "Reduction in force"),
+        notice("Cape Cod Crafts", "", "Plant shutdown"),
+        notice("Dedham Dairy", "", "Layoffs, then closing"),
+        notice("Essex Electric", "", ""),
+    ];
+    notices
+}
+
+fn filter(kinds: &[NoticeKind], min_confidence: ClassificationConfidence) -> Vec<String> {
+    NoticeCollector::filter_by_classification(classified_notices(), kinds, min_confidence)
+        .notices
+        .iter()
+        .map(|notice| notice.firm_name().to_string())
+        .collect()
+}
+
+#[test]
+fn keeps_every_notice_without_kinds_or_confidence() {
+    assert_eq!(
+        filter(&[], ClassificationConfidence::CONFIDENCE_NONE).len(),
+        5
+    );
+}
+
+#[test]
+fn keeps_only_the_kinds_given() {
+    assert_eq!(
+        filter(
+            &[NoticeKind::KIND_CLOSURE],
+            ClassificationConfidence::CONFIDENCE_NONE
+        ),
+        vec!["Acme Widgets", "Cape Cod Crafts", "Dedham Dairy"]
+    );
+    assert_eq!(
+        filter(
+            &[NoticeKind::KIND_LAYOFF, NoticeKind::KIND_UNCLASSIFIED],
+            ClassificationConfidence::CONFIDENCE_NONE
+        ),
+        vec!["Bay State Bakery", "Essex Electric"]
+    );
+}
+
+#[test]
+fn keeps_only_notices_classified_with_enough_confidence() {
+    assert_eq!(
+        filter(&[], ClassificationConfidence::CONFIDENCE_HIGH),
+        vec!["Acme Widgets"]
+    );
+    assert_eq!(
+        filter(
+            &[NoticeKind::KIND_CLOSURE],
+            ClassificationConfidence::CONFIDENCE_MEDIUM
+        ),
+        vec!["Acme Widgets", "Cape Cod Crafts"]
+    );
+    assert_eq!(
+        filter(&[], ClassificationConfidence::CONFIDENCE_LOW),
+        vec![
+            "Acme Widgets",
+            "Bay State Bakery",
+            "Cape Cod Crafts",
+            "Dedham Dairy"
+        ]
+    );
+}
+
+#[test]
+fn uses_the_classification_already_on_a_notice() {
+    let mut notices = classified_notices();
+    NoticeClassifier::annotate_notices(&mut notices);
+    // Overrides what the text would tell
+    let mut classification = notices.notices[1].classification.clone().unwrap();
+    classification.set_kind(NoticeKind::KIND_RELOCATION);
+    notices.notices[1].classification = Some(classification).into();
+
+    let relocations = NoticeCollector::filter_by_classification(
+        notices,
+        &[NoticeKind::KIND_RELOCATION],
+        ClassificationConfidence::CONFIDENCE_NONE,
+    );
+
+    assert_eq!(relocations.notices.len(), 1);
+    assert_eq!(relocations.notices[0].firm_name(), "Bay State Bakery");
+}
+
+#[test]
+fn keeps_only_notices_received_since_the_date() {
+    let received = |firm_name: &str, date_received: &str| {
+        let mut received = notice(firm_name, "", "");
+        received.set_date_received(date_received.to_string());
+        received
+    };
+    let notices = NoticeCollector::to_notices_from_vec(vec![
+        received("Acme Widgets", "2023-09-01"),
+        received("Bay State Bakery", "09/08/2023"),
+        received("Cape Cod Crafts", "2023-09-07"),
+        received("Dedham Dairy", "unknown"),
+    ]);
+
+    let recent = NoticeCollector::filter_received_since(
+        &notices,
+        chrono::NaiveDate::from_ymd_opt(2023, 9, 7).unwrap(),
+    );
+
+    assert_eq!(
+        recent
+            .notices
+            .iter()
+            .map(|notice| notice.firm_name())
+            .collect::<Vec<&str>>(),
+        vec!["Bay State Bakery", "Cape Cod Crafts"]
+    );
+}
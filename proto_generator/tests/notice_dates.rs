@@ -0,0 +1,59 @@
+use chrono::NaiveDate;
+use proto_generator::notice_dates::NoticeDates;
+
+fn date(year: i32, month: u32, day: u32) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+#[test]
+fn parses_every_known_format() {
+    for raw_date in [
+        "2023-09-01",
+        "09/01/2023",
+        "9/1/2023",
+        "9/1/23",
+        "September 1, 2023",
+        "Sep 1, 2023",
+        "September 1 2023",
+        "Sep. 1, 2023",
+        "  2023-09-01 ",
+    ] {
+        assert_eq!(
+            NoticeDates::parse(raw_date),
+            date(2023, 9, 1),
+            "{}",
+            raw_date
+        );
+    }
+}
+
+#[test]
+fn takes_the_first_date_of_a_range_or_list() {
+    assert_eq!(NoticeDates::parse("9/1/2023 - 9/15/2023"), date(2023, 9, 1));
+    assert_eq!(
+        NoticeDates::parse("September 1, 2023 through September 30, 2023"),
+        date(2023, 9, 1)
+    );
+    assert_eq!(NoticeDates::parse("9/1/2023; 10/1/2023"), date(2023, 9, 1));
+    assert_eq!(
+        NoticeDates::parse("9/1/2023 and 10/1/2023"),
+        date(2023, 9, 1)
+    );
+}
+
+#[test]
+fn rejects_what_is_not_a_date() {
+    assert_eq!(NoticeDates::parse(""), None);
+    assert_eq!(NoticeDates::parse("   "), None);
+    assert_eq!(NoticeDates::parse("TBD"), None);
+    assert_eq!(NoticeDates::parse("13/45/2023"), None);
+}
+
+#[test]
+fn parse_month_gives_the_first_of_the_month() {
+    assert_eq!(
+        NoticeDates::parse_month("September 19, 2023"),
+        date(2023, 9, 1)
+    );
+    assert_eq!(NoticeDates::parse_month("TBD"), None);
+}
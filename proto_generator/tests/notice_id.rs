@@ -0,0 +1,135 @@
+use proto_generator::{notice_id::NoticeId, notices::WARNNotice};
+
+fn notice(firm_name: &str, date_received: &str, effective_date: &str) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_date_received(date_received.to_string());
+    notice.set_effective_date(effective_date.to_string());
+    notice
+}
+
+#[test]
+fn stable_id_ignores_casing_and_surrounding_whitespace() {
+    assert_eq!(
+        NoticeId::stable_id(&notice("Acme Widgets", "2023-09-01", "2023-11-01")),
+        NoticeId::stable_id(&notice("  ACME widgets ", "2023-09-01 ", "2023-11-01"))
+    );
+    assert_ne!(
+        NoticeId::stable_id(&notice("Acme Widgets", "2023-09-01", "2023-11-01")),
+        NoticeId::stable_id(&notice("Acme Widgets", "2023-09-01", "2023-12-01"))
+    );
+}
+
+#[test]
+fn stable_id_does_not_depend_on_other_fields() {
+    let mut relocated = notice("Acme Widgets", "2023-09-01", "2023-11-01");
+    relocated.set_firm_locations("Boston".to_string());
+    relocated.set_affected_employees("42".to_string());
+
+    assert_eq!(
+        NoticeId::stable_id(&relocated),
+        NoticeId::stable_id(&notice("Acme Widgets", "2023-09-01", "2023-11-01"))
+    );
+}
+
+#[test]
+fn content_fingerprint_changes_with_any_field() {
+    let original = notice("Acme Widgets", "2023-09-01", "2023-11-01");
+    let mut changed = original.clone();
+    changed.set_affected_employees("42".to_string());
+
+    assert_eq!(
+        NoticeId::content_fingerprint(&original),
+        NoticeId::content_fingerprint(&original.clone())
+    );
+    assert_ne!(
+        NoticeId::content_fingerprint(&original),
+        NoticeId::content_fingerprint(&changed)
+    );
+}
+
+#[test]
+fn fnv1a_matches_reference_values() {
+    assert_eq!(NoticeId::fnv1a(b""), 0xcbf29ce484222325);
+    assert_eq!(NoticeId::fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+    assert_eq!(NoticeId::fnv1a(b"foobar"), 0x85944171f73967e8);
+}
+
+#[test]
+fn unique_ids_suffix_repeated_filings() {
+    let notices = vec![
+        notice("Acme Widgets", "2023-09-01", "2023-11-01"),
+        notice("Bay State Bakery", "2023-09-01", "2023-11-01"),
+        notice("Acme Widgets", "2023-09-01", "2023-11-01"),
+    ];
+    let acme_id = NoticeId::stable_id(&notices[0]);
+
+    assert_eq!(
+        NoticeId::unique_ids(&notices),
+        vec![
+            acme_id.clone(),
+            NoticeId::stable_id(&notices[1]),
+            format!("{}-2", acme_id),
+        ]
+    );
+}
+
+#[test]
+fn content_fingerprint_covers_the_extra_weekly_fields() {
+    let original = notice("Acme Widgets", "2023-09-01", "2023-11-01");
+    let changes: [fn(&mut WARNNotice); 5] = [
+        |notice| notice.set_reason_for_dislocation("Relocation".to_string()),
+        |notice| notice.set_notice_type("Closure".to_string()),
+        |notice| notice.set_union_representation("USW Local 12003".to_string()),
+        |notice| notice.set_contact("Jane Doe".to_string()),
+        |notice| {
+            notice
+                .additional_fields
+                .insert("Industry".to_string(), "Manufacturing".to_string());
+        },
+    ];
+
+    for change in changes {
+        let mut changed = original.clone();
+        change(&mut changed);
+        assert_ne!(
+            NoticeId::content_fingerprint(&original),
+            NoticeId::content_fingerprint(&changed)
+        );
+    }
+}
+
+#[test]
+fn content_fingerprint_ignores_the_order_of_additional_fields() {
+    let mut first = notice("Acme Widgets", "2023-09-01", "2023-11-01");
+    let mut second = first.clone();
+    for (label, value) in [("Industry", "Manufacturing"), ("Shift", "Night")] {
+        first
+            .additional_fields
+            .insert(label.to_string(), value.to_string());
+    }
+    for (label, value) in [("Shift", "Night"), ("Industry", "Manufacturing")] {
+        second
+            .additional_fields
+            .insert(label.to_string(), value.to_string());
+    }
+
+    assert_eq!(
+        NoticeId::content_fingerprint(&first),
+        NoticeId::content_fingerprint(&second)
+    );
+}
+
+#[test]
+fn unique_ids_do_not_depend_on_scrape_order() {
+    let mut boston = notice("Acme Widgets", "2023-09-01", "2023-11-01");
+    boston.set_firm_locations("Boston, MA".to_string());
+    let mut worcester = boston.clone();
+    worcester.set_firm_locations("Worcester, MA".to_string());
+
+    let in_order = NoticeId::unique_ids(&[boston.clone(), worcester.clone()]);
+    let reordered = NoticeId::unique_ids(&[worcester, boston]);
+
+    assert_ne!(in_order[0], in_order[1]);
+    assert_eq!(in_order, vec![reordered[1].clone(), reordered[0].clone()]);
+}
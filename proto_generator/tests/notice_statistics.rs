@@ -0,0 +1,113 @@
+use proto_generator::{
+    notice_statistics::NoticeStatistics,
+    notices::{NoticeTotals, WARNNotice, WARNNotices},
+};
+
+fn notice(
+    firm_name: &str,
+    firm_locations: &str,
+    state: &str,
+    affected_employees: &str,
+) -> WARNNotice {
+    let mut notice = WARNNotice::new();
+    notice.set_firm_name(firm_name.to_string());
+    notice.set_firm_locations(firm_locations.to_string());
+    if !state.is_empty() {
+        notice.set_state(state.to_string());
+    }
+    notice.set_affected_employees(affected_employees.to_string());
+    notice
+}
+
+fn notices(notice_vec: Vec<WARNNotice>) -> WARNNotices {
+    let mut notices = WARNNotices::new();
+    notices.notices = notice_vec;
+    notices
+}
+
+fn keyed(totals: &[NoticeTotals]) -> Vec<(&str, u32, u32)> {
+    totals
+        .iter()
+        .map(|totals| {
+            (
+                totals.key(),
+                totals.notice_count(),
+                totals.affected_employees(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn totals_massachusetts_notices_by_town() {
+    let statistics = NoticeStatistics::compute(
+        &notices(vec![
+            notice("Acme Widgets", "Dorchester and Roxbury", "MA", "40"),
+            notice("Bay State Bakery", "Lowell, Boston", "", "7"),
+            notice("Cape Cod Crafts", "", "MA", "3"),
+        ]),
+        10,
+    );
+
+    assert_eq!(
+        keyed(&statistics.by_town),
+        vec![("Boston", 2, 47), ("Lowell", 1, 7), ("Unknown", 1, 3)]
+    );
+}
+
+#[test]
+fn keys_notices_from_other_states_by_their_location_text() {
+    let statistics = NoticeStatistics::compute(
+        &notices(vec![
+            notice(
+                "Acme Widgets",
+                "1200 Industrial Pkwy, Suite 4, Rochester, NY 14624",
+                "NY",
+                "100",
+            ),
+            notice("Hudson Valley Foods", "Kingston, NY", "NY", "20"),
+        ]),
+        10,
+    );
+
+    assert_eq!(
+        keyed(&statistics.by_town),
+        vec![
+            ("1200 Industrial Pkwy, Suite 4, Rochester, NY 14624", 1, 100),
+            ("Kingston, NY", 1, 20),
+        ]
+    );
+}
+
+fn resolved(firm_name: &str, company_id: &str, company_name: &str, affected: &str) -> WARNNotice {
+    let mut notice = notice(firm_name, "", "", affected);
+    notice.set_company_id(company_id.to_string());
+    notice.set_company_name(company_name.to_string());
+    notice
+}
+
+#[test]
+fn totals_employers_by_company_id() {
+    let statistics = NoticeStatistics::compute(
+        &notices(vec![
+            resolved("Acme Inc", "1", "Acme Inc", "40"),
+            resolved("ACME, Inc.", "1", "Acme Inc", "25"),
+            // Another company resolved to the same name by an alias file
+            resolved("Acme Labs", "2", "Acme Inc", "10"),
+            notice("Bay State Bakery", "", "", "7"),
+            notice(" bay state bakery", "", "", "3"),
+            notice("", "", "", "1"),
+        ]),
+        10,
+    );
+
+    assert_eq!(
+        keyed(&statistics.top_employers),
+        vec![
+            ("Acme Inc", 2, 65),
+            ("Bay State Bakery", 2, 10),
+            ("Acme Inc", 1, 10),
+            ("Unknown", 1, 1),
+        ]
+    );
+}
@@ -1,22 +1,232 @@
-use clap::{Args, Parser, Subcommand};
+use std::{fs, path::PathBuf, time::Duration};
 
-use proto_generator::{notice_collector::NoticeCollector, notices::WARNNotices};
-use web_scraper::scraper::ScraperAdapter;
+use chrono::{Local, Utc};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 
-use protobuf_json_mapping;
+use proto_generator::{
+    company_resolver::CompanyResolver,
+    company_timeline::CompanyTimelineBuilder,
+    exporters::{html_report::HtmlReportExporter, NoticeExporter},
+    notice_collector::NoticeCollector,
+    notice_statistics::NoticeStatistics,
+    notice_store::NoticeStore,
+    notices::{ClassificationConfidence, NoticeKind, WARNNotices},
+};
+use web_scraper::{
+    cassette::Cassette,
+    company_aliases::CompanyAliases,
+    massachusetts_layout::MassachusettsLayout,
+    scraper::MASSACHUSETTS_SOURCE_ID,
+    scraper_config::{
+        ScraperConfig, DEFAULT_DOWNLOAD_DIRECTORY, DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+        DEFAULT_MAX_RETRIES, DEFAULT_MAX_RETRY_DELAY, DEFAULT_MIN_REQUEST_INTERVAL,
+        DEFAULT_RETRY_DELAY, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT,
+    },
+    spreadsheet_source::SpreadsheetSourceConfig,
+    warn_source::{SourceRegistry, WarnSource},
+};
 
-const PAGE_BASE_URL: &str = "https://www.mass.gov";
-const MAIN_PAGE_URL: &str = "/info-details/worker-adjustment-and-retraining-act-warn-weekly-report";
+use crate::{
+    logging::LoggingOptions, output::OutputOptions, stats_output::StatsFormat,
+    timeline_output::TimelineFormat,
+};
 
+const DEFAULT_REPORT_TITLE: &str = "Massachusetts WARN Notices";
+const DAYS_IN_WEEK: i64 = 7;
+
+/// Scrapes WARN notices from the states that publish them
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 pub(crate) struct ScraperCli {
     #[clap(subcommand)]
     pub(crate) command: ScraperCommands,
 
-    /// Set this flag to make the program verbose
-    #[arg(global = true, short, long, default_value_t = false)]
-    verbose: bool,
+    #[command(flatten)]
+    scraping: ScrapingOptions,
+
+    #[command(flatten)]
+    output: OutputOptions,
+
+    #[command(flatten)]
+    logging: LoggingOptions,
+}
+
+/// Options controlling what is scraped
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ScrapingOptions {
+    /// Ids of the sources to scrape notices from. Separate several sources
+    /// with commas. Use the sources command to list them.
+    #[arg(
+        global = true,
+        long = "source",
+        value_delimiter = ',',
+        default_value = MASSACHUSETTS_SOURCE_ID
+    )]
+    sources: Vec<String>,
+
+    /// Config files describing spreadsheet sources to make available, in
+    /// addition to the built in ones. Can be given several times. See
+    /// source_configs/example_spreadsheet_source.toml for the format.
+    #[arg(global = true, long = "source-config")]
+    source_configs: Vec<PathBuf>,
+
+    /// Seconds a single request may take before giving up on it
+    #[arg(global = true, long, default_value_t = DEFAULT_TIMEOUT.as_secs())]
+    timeout: u64,
+
+    /// User agent sent with every request
+    #[arg(global = true, long, default_value = DEFAULT_USER_AGENT)]
+    user_agent: String,
+
+    /// Email or url site owners can reach you at, added to the user agent
+    #[arg(global = true, long)]
+    contact: Option<String>,
+
+    /// Proxy to send every request through, i.e. http://proxy.corp:3128.
+    /// Defaults to the HTTP_PROXY and HTTPS_PROXY environment variables.
+    #[arg(global = true, long)]
+    proxy: Option<String>,
+
+    /// Host or domain to request directly rather than through the proxy,
+    /// whether from --proxy or the environment. Can be given several times,
+    /// or as a comma separated list.
+    #[arg(global = true, long, value_delimiter = ',')]
+    no_proxy: Vec<String>,
+
+    /// PEM file of root certificates to trust on top of the system's. Can be
+    /// given several times.
+    #[arg(global = true, long = "ca-cert")]
+    ca_certs: Vec<PathBuf>,
+
+    /// Save every response received to the directory, to replay later with
+    /// --replay
+    #[arg(global = true, long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Serve responses saved with --record from the directory instead of
+    /// requesting them
+    #[arg(global = true, long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Fetch pages even when the site's robots.txt disallows it
+    #[arg(global = true, long, default_value_t = false)]
+    ignore_robots_txt: bool,
+
+    /// Directory spreadsheets are downloaded into before being parsed
+    #[arg(global = true, long, default_value = DEFAULT_DOWNLOAD_DIRECTORY)]
+    download_dir: PathBuf,
+
+    /// Only scrape the notices listed on the Massachusetts weekly page,
+    /// skipping the year to date spreadsheet
+    #[arg(global = true, long, default_value_t = false)]
+    skip_year_to_date: bool,
+
+    /// Times a failed request is retried
+    #[arg(global = true, long, default_value_t = DEFAULT_MAX_RETRIES)]
+    retries: u32,
+
+    /// Milliseconds to wait before the first retry, doubling with each retry
+    #[arg(global = true, long, default_value_t = DEFAULT_RETRY_DELAY.as_millis() as u64)]
+    retry_delay: u64,
+
+    /// Longest milliseconds to wait between retries. Servers asking for a
+    /// longer wait are given up on.
+    #[arg(global = true, long, default_value_t = DEFAULT_MAX_RETRY_DELAY.as_millis() as u64)]
+    max_retry_delay: u64,
+
+    /// Fewest milliseconds between two requests to the same host
+    #[arg(global = true, long, default_value_t = DEFAULT_MIN_REQUEST_INTERVAL.as_millis() as u64)]
+    min_request_interval: u64,
+
+    /// Most workbooks, or New York notice pages, a source downloads at once
+    #[arg(global = true, long, default_value_t = DEFAULT_MAX_CONCURRENT_DOWNLOADS)]
+    max_concurrent_downloads: usize,
+
+    /// Layout file overriding the selectors and labels the Massachusetts page
+    /// is parsed by. See source_configs/massachusetts_layout.toml.
+    #[arg(global = true, long, value_name = "FILE")]
+    ma_layout: Option<PathBuf>,
+
+    /// File tying together the names a company files notices under, so its
+    /// notices are searched and counted together. See
+    /// source_configs/company_aliases.toml.
+    #[arg(global = true, long, value_name = "FILE")]
+    company_aliases: Option<PathBuf>,
+
+    /// Directory to keep every notice scraped in, one file per fiscal year.
+    /// Years the states no longer publish stay available to the company
+    /// command.
+    #[arg(global = true, long, value_name = "DIR")]
+    notice_store: Option<PathBuf>,
+
+    /// Url of a Massachusetts year to date workbook of an earlier year to
+    /// merge in. Can be given several times.
+    #[arg(global = true, long = "archive-workbook")]
+    archive_workbooks: Vec<String>,
+}
+
+impl ScrapingOptions {
+    /// The built in sources plus those described by the source configs
+    fn registry(&self) -> SourceRegistry {
+        let mut registry = SourceRegistry::default();
+        for config_path in &self.source_configs {
+            registry.register_spreadsheet(
+                SpreadsheetSourceConfig::load(config_path).expect("Error loading source config"),
+            );
+        }
+        registry
+    }
+
+    /// Resolves notices to companies, with the aliases of the alias file
+    fn company_resolver(&self) -> CompanyResolver {
+        match &self.company_aliases {
+            Some(aliases_path) => {
+                CompanyAliases::load(aliases_path).expect("Error loading company aliases")
+            }
+            None => CompanyResolver::default(),
+        }
+    }
+
+    fn notice_store(&self) -> Option<NoticeStore> {
+        self.notice_store
+            .as_ref()
+            .map(|directory| NoticeStore::new(directory))
+    }
+
+    fn scraper_config(&self) -> ScraperConfig {
+        let mut builder = ScraperConfig::builder()
+            .timeout(Duration::from_secs(self.timeout))
+            .user_agent(self.user_agent.clone())
+            .respect_robots_txt(!self.ignore_robots_txt)
+            .no_proxy(self.no_proxy.clone())
+            .root_certificates(self.ca_certs.clone())
+            .download_directory(self.download_dir.clone())
+            .include_year_to_date(!self.skip_year_to_date)
+            .max_retries(self.retries)
+            .retry_delay(Duration::from_millis(self.retry_delay))
+            .max_retry_delay(Duration::from_millis(self.max_retry_delay))
+            .min_request_interval(Duration::from_millis(self.min_request_interval))
+            .max_concurrent_downloads(self.max_concurrent_downloads)
+            .archive_workbook_urls(self.archive_workbooks.clone());
+        if let Some(contact) = &self.contact {
+            builder = builder.contact(contact.clone());
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.clone());
+        }
+        if let Some(layout_path) = &self.ma_layout {
+            builder = builder.massachusetts_layout(
+                MassachusettsLayout::load(layout_path).expect("Error loading Massachusetts layout"),
+            );
+        }
+        if let Some(directory) = &self.record {
+            builder = builder.cassette(Cassette::Record(directory.clone()));
+        }
+        if let Some(directory) = &self.replay {
+            builder = builder.cassette(Cassette::Replay(directory.clone()));
+        }
+        builder.build()
+    }
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -25,49 +235,295 @@ pub(crate) enum ScraperCommands {
     /// If used, will just display all notices
     GetAll(GetAllNotices),
     Search(SearchForNotice),
+    Report(GenerateReport),
+    Stats(ComputeStatistics),
+    Sources(ListSources),
+    Doctor(CheckLayout),
+    Company(ShowCompanyTimeline),
 }
 
 /// Command to just print all notices
 #[derive(Args, Clone, Debug)]
 pub(crate) struct GetAllNotices {}
 
-/// Searches through all notices for the provided company name.
+/// Searches through all notices for the provided company name, and of the
+/// kinds given.
 /// Print result as json
 #[derive(Args, Clone, Debug)]
 pub(crate) struct SearchForNotice {
+    /// Part of the company name, ignoring case. Matches every notice if left
+    /// out.
+    company_name: Option<String>,
+
+    /// Only notices of these kinds. Separate several kinds with commas.
+    #[arg(long = "kind", value_enum, value_delimiter = ',')]
+    kinds: Vec<KindFilter>,
+
+    /// Only notices whose kind was told with at least this confidence
+    #[arg(long, value_enum, default_value_t = ConfidenceFilter::None)]
+    min_confidence: ConfidenceFilter,
+}
+
+/// Kinds of notice search can be narrowed to
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum KindFilter {
+    Closure,
+    Layoff,
+    Relocation,
+    /// Notices nothing told the kind of
+    Unclassified,
+}
+
+impl From<KindFilter> for NoticeKind {
+    fn from(kind: KindFilter) -> Self {
+        match kind {
+            KindFilter::Closure => NoticeKind::KIND_CLOSURE,
+            KindFilter::Layoff => NoticeKind::KIND_LAYOFF,
+            KindFilter::Relocation => NoticeKind::KIND_RELOCATION,
+            KindFilter::Unclassified => NoticeKind::KIND_UNCLASSIFIED,
+        }
+    }
+}
+
+/// Least confidence a notice's kind may have been told with
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum ConfidenceFilter {
+    /// Any notice, classified or not
+    None,
+    /// Keywords of several kinds were found in the notice's text
+    Low,
+    /// Keywords of one kind were found in the notice's text
+    Medium,
+    /// A field stating the kind was filled in
+    High,
+}
+
+impl From<ConfidenceFilter> for ClassificationConfidence {
+    fn from(confidence: ConfidenceFilter) -> Self {
+        match confidence {
+            ConfidenceFilter::None => ClassificationConfidence::CONFIDENCE_NONE,
+            ConfidenceFilter::Low => ClassificationConfidence::CONFIDENCE_LOW,
+            ConfidenceFilter::Medium => ClassificationConfidence::CONFIDENCE_MEDIUM,
+            ConfidenceFilter::High => ClassificationConfidence::CONFIDENCE_HIGH,
+        }
+    }
+}
+
+/// Renders all notices into a self-contained HTML report, with a section for
+/// the notices submitted this past week
+#[derive(Args, Clone, Debug)]
+pub(crate) struct GenerateReport {
+    /// Path to write the report to
+    #[arg(short, long, default_value = "warn_report.html")]
+    output: PathBuf,
+
+    /// Template to render instead of the default one. See
+    /// proto_generator/templates/report.html for the placeholders available.
+    #[arg(short, long)]
+    template: Option<PathBuf>,
+
+    /// Title of the report
+    #[arg(long, default_value = DEFAULT_REPORT_TITLE)]
+    title: String,
+}
+
+/// Computes totals over all notices: by month received, by effective month,
+/// by town and for the top employers
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ComputeStatistics {
+    /// Number of employers to list, ranked by affected employees
+    #[arg(short = 'n', long, default_value_t = 10)]
+    top: usize,
+
+    /// Format to print the statistics in
+    #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+    stats_format: StatsFormat,
+
+    /// Only count the notices of this company, by part of its name or its
+    /// company id. Notices filed under any of its names are counted.
+    #[arg(long)]
+    company: Option<String>,
+}
+
+/// Lists every source notices can be scraped from
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ListSources {}
+
+/// Checks that the pages of the selected sources still have the layout their
+/// parsers expect, listing each anchor found or missing with HTML excerpts.
+/// Exits with an error if any is missing.
+#[derive(Args, Clone, Debug)]
+pub(crate) struct CheckLayout {}
+
+/// Shows every notice of the companies matching the name as a timeline,
+/// oldest first, with running totals of notices and affected employees.
+/// Notices of every fiscal year kept in --notice-store are included, and
+/// only scraped anew with --refresh or while the store is empty.
+#[derive(Args, Clone, Debug)]
+pub(crate) struct ShowCompanyTimeline {
+    /// Part of the company name, ignoring case, or its company id
     company_name: String,
+
+    /// Format to print the timelines in
+    #[arg(long, value_enum, default_value_t = TimelineFormat::Table)]
+    timeline_format: TimelineFormat,
+
+    /// Scrape the sources and update --notice-store before showing the
+    /// timeline. Without a store the sources are always scraped.
+    #[arg(long)]
+    refresh: bool,
 }
 
 impl PerformScraping for GetAllNotices {
-    fn run_command(&self, is_verbose: bool) {
-        let found_notices: WARNNotices = self.do_scraping(is_verbose);
+    fn run_command(&self, scraping: &ScrapingOptions, output: &OutputOptions) {
+        let found_notices: WARNNotices = self.do_scraping(scraping);
         let merged_notices = found_notices
             .notices
             .into_iter()
             .map(NoticeCollector::to_notices)
             .reduce(NoticeCollector::reduce_notices)
             .unwrap();
-        let json_string = protobuf_json_mapping::print_to_string(&merged_notices).unwrap();
-        println!("{}", json_string);
+        println!("{}", output.render(&merged_notices));
     }
 }
 
 impl PerformScraping for SearchForNotice {
-    fn run_command(&self, is_verbose: bool) {
-        let found_notices: WARNNotices = self.do_scraping(is_verbose);
-        let found_matches: WARNNotices =
-            NoticeCollector::search_notices_for_company(found_notices, &self.company_name);
-        let found_json_string = protobuf_json_mapping::print_to_string(&found_matches).unwrap();
-        println!("{}", found_json_string);
+    fn run_command(&self, scraping: &ScrapingOptions, output: &OutputOptions) {
+        let found_notices: WARNNotices = self.do_scraping(scraping);
+        let found_matches: WARNNotices = match &self.company_name {
+            Some(company_name) => {
+                NoticeCollector::search_notices_for_company(found_notices, company_name)
+            }
+            None => found_notices,
+        };
+        let found_matches = NoticeCollector::filter_by_classification(
+            found_matches,
+            &self
+                .kinds
+                .iter()
+                .map(|kind| NoticeKind::from(*kind))
+                .collect::<Vec<NoticeKind>>(),
+            self.min_confidence.into(),
+        );
+        println!("{}", output.render(&found_matches));
+    }
+}
+
+impl PerformScraping for GenerateReport {
+    fn run_command(&self, scraping: &ScrapingOptions, _output: &OutputOptions) {
+        let sources = self.create_sources(scraping);
+        let found_notices: WARNNotices = SourceRegistry::fetch_notices(&sources, false).unwrap();
+        let week_start = Local::now().date_naive() - chrono::Duration::days(DAYS_IN_WEEK);
+        let weekly_notices = NoticeCollector::filter_received_since(&found_notices, week_start);
+
+        let template = self.template.as_ref().map(|template_path| {
+            fs::read_to_string(template_path).expect("Error reading the report template")
+        });
+        let report =
+            HtmlReportExporter::new(self.title.clone(), Utc::now(), weekly_notices, template)
+                .export(&found_notices);
+
+        fs::write(&self.output, report).expect("Error writing the report");
+        println!("Report written to {}", self.output.display());
+    }
+}
+
+impl PerformScraping for ComputeStatistics {
+    fn run_command(&self, scraping: &ScrapingOptions, output: &OutputOptions) {
+        output.reject_format("stats", "--stats-format");
+        let found_notices: WARNNotices = match &self.company {
+            Some(company) => {
+                NoticeCollector::search_notices_for_company(self.do_scraping(scraping), company)
+            }
+            None => self.do_scraping(scraping),
+        };
+        let statistics = NoticeStatistics::compute(&found_notices, self.top);
+        println!("{}", self.stats_format.render(&statistics));
+    }
+}
+
+impl PerformScraping for ListSources {
+    fn run_command(&self, scraping: &ScrapingOptions, _output: &OutputOptions) {
+        for (id, name) in scraping.registry().sources() {
+            println!("{}\t{}", id, name);
+        }
+    }
+}
+
+impl PerformScraping for CheckLayout {
+    fn run_command(&self, scraping: &ScrapingOptions, _output: &OutputOptions) {
+        let mut healthy = true;
+        for source in self.create_sources(scraping) {
+            match source.diagnose_layout() {
+                Some(report) => {
+                    healthy &= report.is_healthy();
+                    println!("{}", report);
+                }
+                None => println!("{}\n  No layout checks for this source\n", source.id()),
+            }
+        }
+
+        if !healthy {
+            eprintln!("Some anchors the parsers rely on are missing");
+            std::process::exit(1);
+        }
+    }
+}
+
+impl PerformScraping for ShowCompanyTimeline {
+    fn run_command(&self, scraping: &ScrapingOptions, output: &OutputOptions) {
+        output.reject_format("company", "--timeline-format");
+        let mut notices = match scraping.notice_store() {
+            Some(store) => {
+                let mut stored = store.load().expect("Error loading the notice store");
+                if self.refresh || stored.notices.is_empty() {
+                    self.do_scraping(scraping);
+                    stored = store.load().expect("Error loading the notice store");
+                }
+                stored
+            }
+            None => self.do_scraping(scraping),
+        };
+        // Stored years may spell the company differently than this one
+        scraping.company_resolver().resolve(&mut notices);
+
+        let company_notices =
+            NoticeCollector::search_notices_for_company(notices, &self.company_name);
+        if company_notices.notices.is_empty() {
+            eprintln!("No notices of a company matching {}", self.company_name);
+            std::process::exit(1);
+        }
+        let timelines = CompanyTimelineBuilder::build(&company_notices);
+        println!("{}", self.timeline_format.render(&timelines));
     }
 }
 
 impl ScraperCli {
     pub fn run() {
         let scraper_cli = ScraperCli::parse();
+        scraper_cli.logging.init();
         match scraper_cli.command {
-            ScraperCommands::GetAll(notices) => notices.run_command(scraper_cli.verbose),
-            ScraperCommands::Search(search) => search.run_command(scraper_cli.verbose),
+            ScraperCommands::GetAll(notices) => {
+                notices.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
+            ScraperCommands::Search(search) => {
+                search.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
+            ScraperCommands::Report(report) => {
+                report.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
+            ScraperCommands::Stats(stats) => {
+                stats.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
+            ScraperCommands::Sources(sources) => {
+                sources.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
+            ScraperCommands::Doctor(doctor) => {
+                doctor.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
+            ScraperCommands::Company(company) => {
+                company.run_command(&scraper_cli.scraping, &scraper_cli.output)
+            }
         }
     }
 }
@@ -75,12 +531,27 @@ impl ScraperCli {
 /// Common interface that most command will need to implement to be valid.
 /// Also provides common implementation of performing scraping
 trait PerformScraping {
-    fn do_scraping(&self, is_verbose: bool) -> WARNNotices {
-        let scraper_adapter =
-            ScraperAdapter::new(PAGE_BASE_URL.to_string(), MAIN_PAGE_URL, is_verbose).unwrap();
-        scraper_adapter.get_notices().unwrap()
+    fn create_sources(&self, scraping: &ScrapingOptions) -> Vec<Box<dyn WarnSource>> {
+        scraping
+            .registry()
+            .create_all(&scraping.sources, &scraping.scraper_config())
+            .unwrap()
+    }
+
+    /// Fetches every notice, keeps them in the notice store if there is one,
+    /// and resolves each to its company
+    fn do_scraping(&self, scraping: &ScrapingOptions) -> WARNNotices {
+        let mut notices =
+            SourceRegistry::fetch_notices(&self.create_sources(scraping), false).unwrap();
+        if let Some(store) = scraping.notice_store() {
+            store
+                .save(&notices)
+                .expect("Error saving notices to the notice store");
+        }
+        scraping.company_resolver().resolve(&mut notices);
+        notices
     }
 
     /// Each implementers specific way to run a command
-    fn run_command(&self, is_verbose: bool);
+    fn run_command(&self, scraping: &ScrapingOptions, output: &OutputOptions);
 }
@@ -0,0 +1,104 @@
+//! Where and how the scraper's logs are written. Logs never go to stdout, so
+//! they can't end up mixed into the notices printed.
+use std::{fs::File, path::PathBuf, sync::Mutex};
+
+use clap::{Args, ValueEnum};
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+/// Crates whose logs --verbose turns up
+const LOGGED_CRATES: [&str; 4] = [
+    "web_scraper",
+    "web_scraper_cli",
+    "scraper_cli",
+    "proto_generator",
+];
+
+/// Formats log lines can be written in
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum LogFormat {
+    /// Human readable lines
+    Text,
+    /// One json object per line
+    Json,
+}
+
+/// Options controlling logging
+#[derive(Args, Clone, Debug)]
+pub(crate) struct LoggingOptions {
+    /// Log more of what the program is doing. Repeat for more detail
+    /// (-v info, -vv debug, -vvv trace).
+    #[arg(global = true, short, long, action = clap::ArgAction::Count)]
+    verbose: u8,
+
+    /// Level to log at, overriding --verbose. Takes a level (i.e. debug) or
+    /// a full filter (i.e. web_scraper=trace). RUST_LOG is used when neither
+    /// is given.
+    #[arg(global = true, long)]
+    log_level: Option<String>,
+
+    /// File to append logs to instead of stderr
+    #[arg(global = true, long)]
+    log_file: Option<PathBuf>,
+
+    /// Format to write logs in
+    #[arg(global = true, long, value_enum, default_value_t = LogFormat::Text)]
+    log_format: LogFormat,
+}
+
+impl LoggingOptions {
+    /// Installs the global subscriber. Must only be called once.
+    pub(crate) fn init(&self) {
+        let builder = tracing_subscriber::fmt().with_env_filter(self.env_filter());
+
+        match (&self.log_file, self.log_format) {
+            (Some(log_file), LogFormat::Text) => builder
+                .with_ansi(false)
+                .with_writer(Mutex::new(Self::open_log_file(log_file)))
+                .init(),
+            (Some(log_file), LogFormat::Json) => builder
+                .json()
+                .with_writer(Mutex::new(Self::open_log_file(log_file)))
+                .init(),
+            (None, LogFormat::Text) => builder.with_writer(std::io::stderr).init(),
+            (None, LogFormat::Json) => builder.json().with_writer(std::io::stderr).init(),
+        }
+    }
+
+    fn env_filter(&self) -> EnvFilter {
+        if let Some(log_level) = &self.log_level {
+            return EnvFilter::try_new(log_level).expect("Invalid --log-level");
+        }
+
+        let level = match self.verbose {
+            0 => {
+                return EnvFilter::builder()
+                    .with_default_directive(LevelFilter::WARN.into())
+                    .from_env_lossy()
+            }
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        };
+        // Dependencies such as hyper are very chatty below warn, so the
+        // level only applies to this project's crates
+        LOGGED_CRATES.iter().fold(
+            EnvFilter::default().add_directive(LevelFilter::WARN.into()),
+            |filter, crate_name| {
+                filter.add_directive(
+                    format!("{}={}", crate_name, level)
+                        .parse()
+                        .expect("Invalid log directive"),
+                )
+            },
+        )
+    }
+
+    fn open_log_file(log_file: &PathBuf) -> File {
+        File::options()
+            .create(true)
+            .append(true)
+            .open(log_file)
+            .expect("Error opening the log file")
+    }
+}
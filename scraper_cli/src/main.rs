@@ -1,4 +1,8 @@
 mod cli;
+mod logging;
+mod output;
+mod stats_output;
+mod timeline_output;
 
 use crate::cli::ScraperCli;
 
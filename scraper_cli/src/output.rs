@@ -0,0 +1,97 @@
+//! Rendering of the notices found into the format the user asked for
+use std::path::PathBuf;
+
+use chrono::Utc;
+use clap::{error::ErrorKind, Args, ValueEnum};
+use proto_generator::{
+    exporters::{
+        atom_feed::{AtomFeedExporter, FeedState},
+        geojson::GeoJsonExporter,
+        icalendar::ICalendarExporter,
+        markdown::MarkdownExporter,
+        NoticeExporter,
+    },
+    notices::WARNNotices,
+};
+
+const NOTICES_TITLE: &str = "Massachusetts WARN Notices";
+const FEED_ID: &str = "urn:warn-scraper:notices";
+
+/// Formats the notices can be printed in
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum OutputFormat {
+    /// Json mapping of the notices protobuf
+    Json,
+    /// Atom feed with one entry per notice
+    Atom,
+    /// iCalendar with an all-day event on each notice's effective date
+    Ics,
+    /// Markdown tables grouped by effective month, for wikis and chat
+    Markdown,
+    /// GeoJSON FeatureCollection with a point for each town named in a notice
+    Geojson,
+}
+
+/// Options controlling how the notices are output
+#[derive(Args, Clone, Debug)]
+pub(crate) struct OutputOptions {
+    /// Format to print the notices in. Defaults to json.
+    #[arg(global = true, long, value_enum)]
+    pub(crate) format: Option<OutputFormat>,
+
+    /// File remembering the atom feed's entries between runs. With it, an
+    /// entry's updated timestamp only changes when the notice itself does.
+    #[arg(global = true, long)]
+    pub(crate) feed_state: Option<PathBuf>,
+}
+
+impl OutputOptions {
+    /// Renders the notices in the requested format
+    pub(crate) fn render(&self, notices: &WARNNotices) -> String {
+        match self.format.unwrap_or(OutputFormat::Json) {
+            OutputFormat::Json => protobuf_json_mapping::print_to_string(notices).unwrap(),
+            OutputFormat::Atom => self.render_atom(notices),
+            OutputFormat::Ics => {
+                ICalendarExporter::new(NOTICES_TITLE.to_string(), Utc::now()).export(notices)
+            }
+            OutputFormat::Markdown => {
+                MarkdownExporter::new(NOTICES_TITLE.to_string(), Utc::now()).export(notices)
+            }
+            OutputFormat::Geojson => GeoJsonExporter::new().export(notices),
+        }
+    }
+
+    /// Exits with an error if --format was given to a command that prints
+    /// something other than notices
+    /// # Params
+    /// * command - Name of the command
+    /// * format_flag - The command's own flag picking its format
+    pub(crate) fn reject_format(&self, command: &str, format_flag: &str) {
+        if self.format.is_some() {
+            clap::Error::raw(
+                ErrorKind::ArgumentConflict,
+                format!(
+                    "--format does not apply to {}, use {} instead\n",
+                    command, format_flag
+                ),
+            )
+            .exit();
+        }
+    }
+
+    fn render_atom(&self, notices: &WARNNotices) -> String {
+        let now = Utc::now();
+        let mut state = match &self.feed_state {
+            Some(path) => FeedState::load(path).expect("Error loading the feed state"),
+            None => FeedState::default(),
+        };
+        state.refresh(notices, now);
+
+        if let Some(path) = &self.feed_state {
+            state.save(path).expect("Error saving the feed state");
+        }
+
+        AtomFeedExporter::new(NOTICES_TITLE.to_string(), FEED_ID.to_string(), now, state)
+            .export(notices)
+    }
+}
@@ -0,0 +1,103 @@
+//! Rendering of the aggregate statistics into the format the user asked for
+use clap::ValueEnum;
+use proto_generator::notices::{NoticeTotals, WARNStatistics};
+
+const CSV_HEADER: &str = "category,key,notice_count,affected_employees";
+
+/// Formats the statistics can be printed in
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StatsFormat {
+    /// Human readable tables
+    Table,
+    /// Json mapping of the statistics protobuf
+    Json,
+    /// One row per total, with a column naming the category it belongs to
+    Csv,
+}
+
+impl StatsFormat {
+    pub(crate) fn render(&self, statistics: &WARNStatistics) -> String {
+        match self {
+            StatsFormat::Table => Self::render_table(statistics),
+            StatsFormat::Json => protobuf_json_mapping::print_to_string(statistics).unwrap(),
+            StatsFormat::Csv => Self::render_csv(statistics),
+        }
+    }
+
+    fn categories(statistics: &WARNStatistics) -> [(&'static str, &Vec<NoticeTotals>); 4] {
+        [
+            ("By Month Received", &statistics.by_month_received),
+            ("By Effective Month", &statistics.by_effective_month),
+            ("By Town", &statistics.by_town),
+            ("Top Employers", &statistics.top_employers),
+        ]
+    }
+
+    fn render_table(statistics: &WARNStatistics) -> String {
+        let mut table = format!(
+            "Notices: {}\nAffected employees: {}\n",
+            statistics.notice_count(),
+            statistics.affected_employees()
+        );
+
+        for (category, totals) in Self::categories(statistics) {
+            let key_width = totals
+                .iter()
+                .map(|total| total.key().chars().count())
+                .chain(std::iter::once(category.len()))
+                .max()
+                .unwrap_or(0);
+
+            table.push_str(&format!(
+                "\n{:<width$}  {:>7}  {:>18}\n",
+                category,
+                "Notices",
+                "Affected Employees",
+                width = key_width
+            ));
+            for total in totals.iter() {
+                table.push_str(&format!(
+                    "{:<width$}  {:>7}  {:>18}\n",
+                    total.key(),
+                    total.notice_count(),
+                    total.affected_employees(),
+                    width = key_width
+                ));
+            }
+        }
+
+        table
+    }
+
+    fn render_csv(statistics: &WARNStatistics) -> String {
+        let mut csv = format!(
+            "{}\noverall,all,{},{}\n",
+            CSV_HEADER,
+            statistics.notice_count(),
+            statistics.affected_employees()
+        );
+
+        for (category, totals) in Self::categories(statistics) {
+            let category = category.to_lowercase().replace(' ', "_");
+            for total in totals.iter() {
+                csv.push_str(&format!(
+                    "{},{},{},{}\n",
+                    category,
+                    Self::escape_csv(total.key()),
+                    total.notice_count(),
+                    total.affected_employees()
+                ));
+            }
+        }
+
+        csv
+    }
+
+    /// Quotes a field if it contains characters with meaning in csv
+    fn escape_csv(raw: &str) -> String {
+        match raw.contains([',', '"', '\n', '\r']) {
+            true => format!("\"{}\"", raw.replace('"', "\"\"")),
+            false => raw.to_string(),
+        }
+    }
+}
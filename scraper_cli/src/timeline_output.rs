@@ -0,0 +1,136 @@
+//! Rendering of company timelines into the format the user asked for
+use clap::ValueEnum;
+use proto_generator::{
+    notice_dates::NoticeDates,
+    notices::{CompanyTimeline, CompanyTimelines},
+};
+
+const CSV_HEADER: &str = "company_id,company_name,date_received,effective_date,locations,\
+                          affected_employees,cumulative_notice_count,cumulative_affected_employees";
+const TABLE_HEADINGS: [&str; 6] = [
+    "Received",
+    "Effective",
+    "Employees",
+    "Total Notices",
+    "Total Employees",
+    "Locations",
+];
+
+/// Formats company timelines can be printed in
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TimelineFormat {
+    /// A human readable table per company
+    Table,
+    /// Json mapping of the timelines protobuf
+    Json,
+    /// One row per notice, with columns naming its company
+    Csv,
+}
+
+impl TimelineFormat {
+    pub(crate) fn render(&self, timelines: &CompanyTimelines) -> String {
+        match self {
+            TimelineFormat::Table => Self::render_tables(timelines),
+            TimelineFormat::Json => protobuf_json_mapping::print_to_string(timelines).unwrap(),
+            TimelineFormat::Csv => Self::render_csv(timelines),
+        }
+    }
+
+    fn render_tables(timelines: &CompanyTimelines) -> String {
+        timelines
+            .timelines
+            .iter()
+            .map(Self::render_table)
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn render_table(timeline: &CompanyTimeline) -> String {
+        let rows = timeline
+            .entries
+            .iter()
+            .map(|entry| {
+                [
+                    Self::render_date(entry.notice.date_received()),
+                    Self::render_date(entry.notice.effective_date()),
+                    entry.notice.affected_employees().trim().to_string(),
+                    entry.cumulative_notice_count().to_string(),
+                    entry.cumulative_affected_employees().to_string(),
+                    entry.notice.firm_locations().trim().to_string(),
+                ]
+            })
+            .collect::<Vec<[String; 6]>>();
+        let widths = (0..TABLE_HEADINGS.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].chars().count())
+                    .chain(std::iter::once(TABLE_HEADINGS[column].len()))
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<usize>>();
+
+        let mut table = format!(
+            "{} (company id {})\n",
+            timeline.company_name(),
+            timeline.company_id()
+        );
+        let headings = TABLE_HEADINGS.map(str::to_string);
+        for row in std::iter::once(&headings).chain(rows.iter()) {
+            table.push_str(&format!(
+                "{:<w0$}  {:<w1$}  {:>w2$}  {:>w3$}  {:>w4$}  {}\n",
+                row[0],
+                row[1],
+                row[2],
+                row[3],
+                row[4],
+                row[5],
+                w0 = widths[0],
+                w1 = widths[1],
+                w2 = widths[2],
+                w3 = widths[3],
+                w4 = widths[4],
+            ));
+        }
+
+        table
+    }
+
+    fn render_csv(timelines: &CompanyTimelines) -> String {
+        let mut csv = format!("{}\n", CSV_HEADER);
+        for timeline in timelines.timelines.iter() {
+            for entry in timeline.entries.iter() {
+                csv.push_str(&format!(
+                    "{},{},{},{},{},{},{},{}\n",
+                    timeline.company_id(),
+                    Self::escape_csv(timeline.company_name()),
+                    Self::escape_csv(entry.notice.date_received()),
+                    Self::escape_csv(entry.notice.effective_date()),
+                    Self::escape_csv(entry.notice.firm_locations()),
+                    Self::escape_csv(entry.notice.affected_employees()),
+                    entry.cumulative_notice_count(),
+                    entry.cumulative_affected_employees()
+                ));
+            }
+        }
+
+        csv
+    }
+
+    /// Dates that could be understood are written as YYYY-MM-DD, so the
+    /// column reads the same whichever source the notice came from
+    fn render_date(raw_date: &str) -> String {
+        match NoticeDates::parse(raw_date) {
+            Some(date) => date.format("%Y-%m-%d").to_string(),
+            None => raw_date.trim().to_string(),
+        }
+    }
+
+    /// Quotes a field if it contains characters with meaning in csv
+    fn escape_csv(raw: &str) -> String {
+        match raw.contains([',', '"', '\n', '\r']) {
+            true => format!("\"{}\"", raw.replace('"', "\"\"")),
+            false => raw.to_string(),
+        }
+    }
+}
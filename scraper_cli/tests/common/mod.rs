@@ -0,0 +1,84 @@
+//! Runs the cli against a spreadsheet source served from a local server
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    thread,
+};
+
+const NOTICES_CSV: &str = "\
+Company,Reason,Type
+Acme Widgets,,Closure
+Bay State Bakery,Reduction in force,
+Cape Cod Crafts,Plant shutdown,
+Dedham Dairy,\"Layoffs, then closing\",
+Essex Electric,,
+";
+
+/// Local server answering every request with the notices csv
+fn serve_notices() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/notices.csv", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                NOTICES_CSV.len(),
+                NOTICES_CSV
+            )
+            .unwrap();
+        }
+    });
+    url
+}
+
+/// A clean directory for a test's source config, downloads and other files
+pub fn work_directory(test_name: &str) -> PathBuf {
+    let work_directory = std::env::temp_dir().join(format!("warn_scraper_cli_{}", test_name));
+    let _ = std::fs::remove_dir_all(&work_directory);
+    std::fs::create_dir_all(&work_directory).unwrap();
+    work_directory
+}
+
+/// A command scraping a spreadsheet source, with id "ex", serving the notices
+/// csv. Arguments after the source's are left to the caller.
+pub fn scraper_command(work_directory: &Path) -> Command {
+    let source_config: PathBuf = work_directory.join("source.toml");
+    std::fs::write(
+        &source_config,
+        format!(
+            "id = \"ex\"\nname = \"Example\"\nstate = \"EX\"\nurl = \"{}\"\n\n\
+             [columns]\nfirm_name = \"Company\"\nreason_for_dislocation = \"Reason\"\n\
+             notice_type = \"Type\"\n",
+            serve_notices()
+        ),
+    )
+    .unwrap();
+
+    let mut command = Command::new(env!("CARGO_BIN_EXE_scraper_cli"));
+    command
+        .env_remove("RUST_LOG")
+        .args(["--source", "ex", "--source-config"])
+        .arg(&source_config)
+        .arg("--download-dir")
+        .arg(work_directory.join("downloads"))
+        .args(["--ignore-robots-txt", "--min-request-interval", "0"]);
+    command
+}
+
+/// Panics with the command's stderr unless it succeeded
+pub fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::{path::Path, process::Command};
+
+use common::{assert_success, scraper_command, work_directory};
+
+/// A company command on the store, whose source can no longer be reached
+fn company_command_offline(work_directory: &Path, extra_args: &[&str]) -> Command {
+    let mut command = scraper_command(work_directory);
+    std::fs::write(
+        work_directory.join("source.toml"),
+        "id = \"ex\"\nname = \"Example\"\nstate = \"EX\"\n\
+         url = \"http://127.0.0.1:1/notices.csv\"\n\n[columns]\nfirm_name = \"Company\"\n",
+    )
+    .unwrap();
+    command
+        .args(["--retries", "0", "--notice-store"])
+        .arg(work_directory.join("store"))
+        .args(["company", "acme", "--timeline-format", "csv"])
+        .args(extra_args);
+    command
+}
+
+#[test]
+fn company_rejects_the_notice_format() {
+    let output = Command::new(env!("CARGO_BIN_EXE_scraper_cli"))
+        .args(["company", "acme", "--format", "json"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("use --timeline-format instead"));
+}
+
+#[test]
+fn company_reads_the_store_unless_refreshed() {
+    let work_directory = work_directory("company_store");
+    // The store is empty, so the first run scrapes to fill it
+    let filled = scraper_command(&work_directory)
+        .arg("--notice-store")
+        .arg(work_directory.join("store"))
+        .args(["company", "acme", "--timeline-format", "csv"])
+        .output()
+        .unwrap();
+
+    let from_store = company_command_offline(&work_directory, &[])
+        .output()
+        .unwrap();
+    let refreshed = company_command_offline(&work_directory, &["--refresh"])
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&work_directory).unwrap();
+
+    assert_success(&filled);
+    assert!(String::from_utf8_lossy(&filled.stdout).contains("Acme Widgets"));
+    assert_success(&from_store);
+    assert_eq!(from_store.stdout, filled.stdout);
+    assert!(!refreshed.status.success());
+}
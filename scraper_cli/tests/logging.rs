@@ -0,0 +1,93 @@
+use std::process::Output;
+
+mod common;
+
+/// Logged by the spreadsheet source at info
+const INFO_EVENT: &str = "Parsed spreadsheets";
+/// Logged by the workbook pipeline at debug
+const DEBUG_EVENT: &str = "Parsing workbook";
+
+/// Scrapes every notice with the logging arguments and environment given
+fn get_all(test_name: &str, logging_args: &[&str], rust_log: Option<&str>) -> Output {
+    let work_directory = common::work_directory(&format!("logging_{}", test_name));
+    let mut command = common::scraper_command(&work_directory);
+    if let Some(rust_log) = rust_log {
+        command.env("RUST_LOG", rust_log);
+    }
+    let output = command.args(logging_args).arg("get-all").output().unwrap();
+    std::fs::remove_dir_all(&work_directory).unwrap();
+    common::assert_success(&output);
+    output
+}
+
+fn logs(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+#[test]
+fn only_warnings_are_logged_by_default() {
+    let output = get_all("default", &[], None);
+
+    assert!(!logs(&output).contains(INFO_EVENT));
+}
+
+#[test]
+fn each_verbose_flag_logs_one_more_level() {
+    let info = logs(&get_all("verbose", &["-v"], None));
+    assert!(info.contains(INFO_EVENT));
+    assert!(!info.contains(DEBUG_EVENT));
+
+    let debug = logs(&get_all("very_verbose", &["-vv"], None));
+    assert!(debug.contains(INFO_EVENT));
+    assert!(debug.contains(DEBUG_EVENT));
+}
+
+#[test]
+fn rust_log_applies_without_verbose_flags() {
+    let from_env = logs(&get_all("rust_log", &[], Some("web_scraper=debug")));
+    assert!(from_env.contains(DEBUG_EVENT));
+
+    // --verbose takes precedence over RUST_LOG
+    let verbose = logs(&get_all(
+        "rust_log_verbose",
+        &["-v"],
+        Some("web_scraper=debug"),
+    ));
+    assert!(verbose.contains(INFO_EVENT));
+    assert!(!verbose.contains(DEBUG_EVENT));
+}
+
+#[test]
+fn log_level_overrides_verbose_flags() {
+    let logs = logs(&get_all("log_level", &["-vv", "--log-level", "info"], None));
+
+    assert!(logs.contains(INFO_EVENT));
+    assert!(!logs.contains(DEBUG_EVENT));
+}
+
+#[test]
+fn logs_go_to_the_log_file_as_json() {
+    let log_file = std::env::temp_dir().join("warn_scraper_cli_logging_file.log");
+    let _ = std::fs::remove_file(&log_file);
+
+    let output = get_all(
+        "log_file",
+        &[
+            "-v",
+            "--log-format",
+            "json",
+            "--log-file",
+            log_file.to_str().unwrap(),
+        ],
+        None,
+    );
+    let file_logs = std::fs::read_to_string(&log_file).unwrap();
+    std::fs::remove_file(&log_file).unwrap();
+
+    assert!(!logs(&output).contains(INFO_EVENT));
+    assert!(!String::from_utf8_lossy(&output.stdout).contains(INFO_EVENT));
+    assert!(file_logs.contains(INFO_EVENT));
+    assert!(file_logs
+        .lines()
+        .all(|line| line.starts_with('{') && line.ends_with('}')));
+}
@@ -0,0 +1,59 @@
+mod common;
+
+/// Runs search against a spreadsheet source serving the notices csv
+/// # Return
+/// The firm names of the notices found
+fn search(test_name: &str, search_args: &[&str]) -> Vec<String> {
+    let work_directory = common::work_directory(&format!("search_{}", test_name));
+    let output = common::scraper_command(&work_directory)
+        .arg("search")
+        .args(search_args)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&work_directory).unwrap();
+    common::assert_success(&output);
+
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .split("\"firmName\": \"")
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn search_keeps_only_the_kinds_given() {
+    assert_eq!(
+        search("kinds", &["--kind", "closure"]),
+        vec!["Acme Widgets", "Cape Cod Crafts", "Dedham Dairy"]
+    );
+    assert_eq!(
+        search("several_kinds", &["--kind", "layoff,unclassified"]),
+        vec!["Bay State Bakery", "Essex Electric"]
+    );
+}
+
+#[test]
+fn search_keeps_only_notices_classified_with_enough_confidence() {
+    assert_eq!(
+        search("high_confidence", &["--min-confidence", "high"]),
+        vec!["Acme Widgets"]
+    );
+    assert_eq!(
+        search(
+            "medium_closures",
+            &["--kind", "closure", "--min-confidence", "medium"]
+        ),
+        vec!["Acme Widgets", "Cape Cod Crafts"]
+    );
+}
+
+#[test]
+fn search_combines_kinds_with_the_company_name() {
+    assert_eq!(
+        search("company_and_kind", &["bakery", "--kind", "layoff"]),
+        vec!["Bay State Bakery"]
+    );
+    assert!(search("company_of_other_kind", &["bakery", "--kind", "closure"]).is_empty());
+}
@@ -0,0 +1,12 @@
+use std::process::Command;
+
+#[test]
+fn stats_rejects_the_notice_format() {
+    let output = Command::new(env!("CARGO_BIN_EXE_scraper_cli"))
+        .args(["stats", "--format", "json"])
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("use --stats-format instead"));
+}
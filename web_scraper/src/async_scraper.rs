@@ -0,0 +1,148 @@
+//! Async scraper of the Massachusetts weekly report page. Fetching and
+//! downloading never block the runtime, and the year to date spreadsheet is
+//! parsed on tokio's blocking thread pool. Only built with the `async` feature;
+//! `ScraperAdapter` is its blocking counterpart.
+use proto_generator::{
+    location_parser::LocationParser, notice_collector::NoticeCollector, notices::WARNNotices,
+};
+use tracing::{info, instrument};
+
+use crate::{
+    error::ScraperResult,
+    http_client::HttpClient,
+    layout_report::LayoutReport,
+    massachusetts_layout::MassachusettsLayout,
+    scraper::{
+        ScraperAdapter, MASSACHUSETTS_BASE_URL, MASSACHUSETTS_SOURCE_ID,
+        MASSACHUSETTS_WEEKLY_REPORT_URL,
+    },
+    scraper_config::ScraperConfig,
+    weekly_report_page::{WeeklyReportPage, YearlyReport},
+    workbook_pipeline::WorkbookPipeline,
+    year_to_date_xslx_parser::YearToDateParser,
+};
+
+/// Scrapes the Massachusetts weekly report page without blocking
+pub struct AsyncScraperAdapter {
+    /// The page is kept as text, as parsed documents can't be held across
+    /// awaits by futures that must be Send
+    main_page_html: String,
+    page_url: String,
+    base_url: String,
+    config: ScraperConfig,
+    client: HttpClient,
+}
+
+impl AsyncScraperAdapter {
+    /// Scraper for the Massachusetts weekly report page on mass.gov
+    pub async fn massachusetts(config: ScraperConfig) -> ScraperResult<AsyncScraperAdapter> {
+        Self::with_config(
+            MASSACHUSETTS_BASE_URL.to_string(),
+            MASSACHUSETTS_WEEKLY_REPORT_URL,
+            config,
+        )
+        .await
+    }
+
+    /// # Params
+    /// * base_url - The base url (i.e. http://foo/)
+    /// * relative_page_to_request_url - the page relative to the base url to request
+    /// * config - How pages and spreadsheets are fetched
+    pub async fn with_config(
+        base_url: String,
+        relative_page_to_request_url: &str,
+        config: ScraperConfig,
+    ) -> ScraperResult<AsyncScraperAdapter> {
+        let full_page_to_request_url =
+            ScraperAdapter::construct_full_url(&base_url, relative_page_to_request_url)?;
+
+        info!(url = %full_page_to_request_url, "Fetching weekly report page");
+        let client = config.http_client()?;
+        let main_page_html = client.get(&full_page_to_request_url).await?.text().await?;
+
+        Ok(AsyncScraperAdapter {
+            main_page_html,
+            page_url: full_page_to_request_url,
+            base_url,
+            config,
+            client,
+        })
+    }
+
+    /// # Return
+    /// The notices listed on the page merged with those of the year to date
+    /// spreadsheet, unless it is configured to be skipped
+    #[instrument(skip(self))]
+    pub async fn get_notices(&self) -> ScraperResult<WARNNotices> {
+        let page = self.parse_page(self.config.include_year_to_date)?;
+        info!(
+            notice_count = page.weekly_notices.notices.len(),
+            "Parsed weekly notices"
+        );
+
+        let mut overall_notices = match page.yearly_report {
+            None => page.weekly_notices,
+            Some(yearly_report) => {
+                let ytd_notices = self.get_current_ytd_warn_notices(yearly_report).await?;
+                NoticeCollector::reduce_notices(page.weekly_notices, ytd_notices)
+            }
+        };
+        LocationParser::annotate_notices(&mut overall_notices);
+        Ok(overall_notices)
+    }
+
+    /// # Return
+    /// Only the notices submitted this past week, as listed on the page itself.
+    /// The year-to-date spreadsheet is not downloaded.
+    #[instrument(skip(self))]
+    pub async fn get_weekly_notices(&self) -> ScraperResult<WARNNotices> {
+        let mut weekly_notices = self.parse_page(false)?.weekly_notices;
+        LocationParser::annotate_notices(&mut weekly_notices);
+        Ok(weekly_notices)
+    }
+
+    /// Checks the fetched page for every anchor the parser relies on
+    pub fn diagnose_layout(&self) -> LayoutReport {
+        Self::check_layout(
+            &self.main_page_html,
+            &self.page_url,
+            &self.config.massachusetts_layout,
+        )
+    }
+
+    /// See `ScraperAdapter::check_layout`
+    pub fn check_layout(html: &str, url: &str, layout: &MassachusettsLayout) -> LayoutReport {
+        WeeklyReportPage::check_layout(html, url, layout)
+    }
+
+    /// Nothing is awaited while the parsed document is alive
+    fn parse_page(&self, find_yearly_report: bool) -> ScraperResult<WeeklyReportPage> {
+        WeeklyReportPage::parse(
+            &self.main_page_html,
+            &self.base_url,
+            &self.config.massachusetts_layout,
+            find_yearly_report,
+        )
+    }
+
+    /// Downloads the year to date spreadsheet, and those of any configured
+    /// archived years, and parses them
+    async fn get_current_ytd_warn_notices(
+        &self,
+        yearly_report: YearlyReport,
+    ) -> ScraperResult<WARNNotices> {
+        let workbooks = yearly_report.workbooks(&self.config.archive_workbook_urls);
+        let download_directory = self.config.download_directory.join(MASSACHUSETTS_SOURCE_ID);
+        let xslx_notices = WorkbookPipeline::new(&self.config, &self.client)
+            .download_and_parse(workbooks, &download_directory, |workbook_path| {
+                YearToDateParser::new(&workbook_path)?.parse_for_notices()
+            })
+            .await?;
+        info!(
+            notice_count = xslx_notices.notices.len(),
+            "Parsed year to date notices"
+        );
+
+        Ok(xslx_notices)
+    }
+}
@@ -0,0 +1,57 @@
+//! Runs blocking work on a bounded number of threads, for the blocking
+//! counterparts of what the async code runs concurrently on the runtime
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use tracing::Span;
+
+pub(crate) struct BoundedThreads {}
+
+impl BoundedThreads {
+    /// # Brief
+    /// Calls `work` on each item, with at most `max_threads` calls running
+    /// at once. Each thread logs within the span of the caller.
+    /// # Return
+    /// The result of each item, in the order the items were given however
+    /// the calls finish
+    pub(crate) fn map_ordered<T, R, F>(items: &[T], max_threads: usize, work: F) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(usize, &T) -> R + Sync,
+    {
+        let next_item = AtomicUsize::new(0);
+        let results: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+        let span = Span::current();
+
+        thread::scope(|scope| {
+            for _ in 0..max_threads.max(1).min(items.len()) {
+                scope.spawn(|| {
+                    let _entered = span.enter();
+                    loop {
+                        let idx = next_item.fetch_add(1, Ordering::SeqCst);
+                        let Some(item) = items.get(idx) else {
+                            break;
+                        };
+                        *results[idx].lock().unwrap() = Some(work(idx, item));
+                    }
+                });
+            }
+        });
+
+        results
+            .into_iter()
+            .map(|result| {
+                result
+                    .into_inner()
+                    .unwrap()
+                    .expect("Every item is worked on before the threads finish")
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,126 @@
+//! Recording of every response the scraper receives, and replaying of them in
+//! place of the network. A recording made while hitting a bug reproduces it
+//! offline, parsing included.
+//!
+//! Each response is saved as two files named after a hash of its url:
+//! `<hash>.toml` with the url, status and headers, and `<hash>.body` with the
+//! body as received.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use proto_generator::notice_id::NoticeId;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+
+use crate::error::{ScraperError, ScraperResult};
+
+/// Whether responses are recorded to or replayed from a directory
+#[derive(Clone, Debug, PartialEq)]
+pub enum Cassette {
+    /// Requests hit the network and their responses are saved to the directory
+    Record(PathBuf),
+    /// Requests never hit the network, responses are read from the directory
+    Replay(PathBuf),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct RecordedHeader {
+    name: String,
+    value: String,
+}
+
+/// What is saved of a response next to its body
+#[derive(Debug, Deserialize, Serialize)]
+struct RecordedResponse {
+    url: String,
+    status: u16,
+    #[serde(default)]
+    headers: Vec<RecordedHeader>,
+}
+
+impl Cassette {
+    /// Saves a response received for the url
+    pub(crate) fn record(
+        directory: &Path,
+        url: &str,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> ScraperResult<()> {
+        let recorded = RecordedResponse {
+            url: url.to_string(),
+            status: status.as_u16(),
+            headers: headers
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some(RecordedHeader {
+                        name: name.to_string(),
+                        value: value.to_str().ok()?.to_string(),
+                    })
+                })
+                .collect(),
+        };
+        let metadata = toml::to_string(&recorded).map_err(|err| {
+            ScraperError::Cassette(format!("serializing response of {}: {}", url, err))
+        })?;
+
+        let (metadata_path, body_path) = Self::paths(directory, url);
+        fs::create_dir_all(directory)?;
+        fs::write(metadata_path, metadata)?;
+        fs::write(body_path, body)?;
+        debug!(url, status = status.as_u16(), "Recorded response");
+
+        Ok(())
+    }
+
+    /// # Return
+    /// The response recorded for the url, ready to be read as if it was
+    /// just received
+    pub(crate) fn replay(directory: &Path, url: &str) -> ScraperResult<http::Response<Vec<u8>>> {
+        let (metadata_path, body_path) = Self::paths(directory, url);
+        let metadata = fs::read_to_string(&metadata_path).map_err(|_| {
+            ScraperError::Cassette(format!(
+                "no response to {} recorded in {}",
+                url,
+                directory.display()
+            ))
+        })?;
+        let recorded: RecordedResponse = toml::from_str(&metadata).map_err(|err| {
+            ScraperError::Cassette(format!("reading {}: {}", metadata_path.display(), err))
+        })?;
+        let body = fs::read(&body_path)?;
+        debug!(url, status = recorded.status, "Replaying response");
+
+        let mut response = http::Response::builder().status(recorded.status);
+        for header in &recorded.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(header.name.as_bytes()),
+                HeaderValue::from_str(&header.value),
+            ) {
+                response = response.header(name, value);
+            }
+        }
+        if let Ok(url) = Url::parse(url) {
+            response = reqwest::ResponseBuilderExt::url(response, url);
+        }
+        response.body(body).map_err(|err| {
+            ScraperError::Cassette(format!("rebuilding response of {}: {}", url, err))
+        })
+    }
+
+    /// Paths of the metadata and body files of the url's response. Named by
+    /// a hash that stays the same across builds and platforms.
+    fn paths(directory: &Path, url: &str) -> (PathBuf, PathBuf) {
+        let name = format!("{:016x}", NoticeId::fnv1a(url.as_bytes()));
+        (
+            directory.join(format!("{}.toml", name)),
+            directory.join(format!("{}.body", name)),
+        )
+    }
+}
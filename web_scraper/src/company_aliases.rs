@@ -0,0 +1,89 @@
+//! User editable file tying together the names a company files notices
+//! under when they are too different to be matched by normalising them, i.e.
+//! a trade name or a former name.
+//!
+//! An alias file is a TOML file such as:
+//! ```toml
+//! [[company]]
+//! name = "Acme Labs"
+//! aliases = ["Acme Incorporated", "Acme Laboratories of New England"]
+//! ```
+use std::{collections::HashMap, fs, path::Path};
+
+use proto_generator::company_resolver::{CompanyAlias, CompanyResolver};
+use serde::Deserialize;
+
+use crate::error::{ScraperError, ScraperResult};
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompanyAliasFile {
+    #[serde(default, rename = "company")]
+    companies: Vec<CompanyEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CompanyEntry {
+    name: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+}
+
+pub struct CompanyAliases {}
+
+impl CompanyAliases {
+    /// Reads and validates an alias file
+    pub fn load(path: &Path) -> ScraperResult<CompanyResolver> {
+        let aliases_text = fs::read_to_string(path)?;
+        Self::parse(&aliases_text).map_err(|err| match err {
+            ScraperError::SourceConfig(message) => {
+                ScraperError::SourceConfig(format!("{}: {}", path.display(), message))
+            }
+            err => err,
+        })
+    }
+
+    /// # Brief
+    /// Parses and validates the text of an alias file. Every name must be
+    /// left with some text once normalised, and no name may belong to two
+    /// companies.
+    /// # Return
+    /// A resolver matching notices to the companies of the file
+    pub fn parse(aliases_text: &str) -> ScraperResult<CompanyResolver> {
+        let file: CompanyAliasFile = toml::from_str(aliases_text)
+            .map_err(|err| ScraperError::SourceConfig(err.to_string()))?;
+
+        let mut owners: HashMap<String, &str> = HashMap::new();
+        for company in &file.companies {
+            for name in std::iter::once(&company.name).chain(company.aliases.iter()) {
+                let normalised = CompanyResolver::normalise(name);
+                if normalised.is_empty() {
+                    return Err(ScraperError::SourceConfig(format!(
+                        "company name {:?} is empty once normalised",
+                        name
+                    )));
+                }
+                match owners.insert(normalised, &company.name) {
+                    Some(owner) if owner != company.name => {
+                        return Err(ScraperError::SourceConfig(format!(
+                            "{} is a name of both {} and {}",
+                            name, owner, company.name
+                        )))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let companies = file
+            .companies
+            .into_iter()
+            .map(|company| CompanyAlias {
+                name: company.name,
+                aliases: company.aliases,
+            })
+            .collect::<Vec<CompanyAlias>>();
+        Ok(CompanyResolver::new(&companies))
+    }
+}
@@ -12,6 +12,16 @@ pub enum ScraperError {
     Downloading(String),
     #[error("Merging Warn Notices")]
     MergingNotices(String),
+    #[error("Invalid source config: {0}")]
+    SourceConfig(String),
+    #[error("Fetching {0} is disallowed by the site's robots.txt")]
+    DisallowedByRobots(String),
+    #[error("Invalid network config: {0}")]
+    NetworkConfig(String),
+    #[error("Cassette error: {0}")]
+    Cassette(String),
+    #[error("Unknown WARN source {0}")]
+    UnknownSource(String),
     #[error("I/O Error")]
     IO(#[from] std::io::Error),
     #[error("Protobuf Error")]
@@ -0,0 +1,129 @@
+//! Structural health checks of scraped pages. When a site changes its markup
+//! the parsers fail on the first thing they can't find; a `LayoutReport`
+//! instead lists every anchor the parsers rely on, whether it was found, and
+//! an excerpt of the HTML around it, so the change can be pinned down.
+use std::fmt;
+
+use scraper::ElementRef;
+
+/// Longest excerpt kept of an element's HTML
+const EXCERPT_LENGTH: usize = 240;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum AnchorStatus {
+    Found,
+    Missing,
+    /// Found more than once where exactly one is expected
+    Ambiguous,
+    /// Not checked, as an anchor it is looked for under is missing
+    Skipped,
+}
+
+impl fmt::Display for AnchorStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            AnchorStatus::Found => "ok",
+            AnchorStatus::Missing => "missing",
+            AnchorStatus::Ambiguous => "ambiguous",
+            AnchorStatus::Skipped => "skipped",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+/// The outcome of looking for one anchor
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnchorCheck {
+    /// Short name of the anchor, i.e. "WARN heading"
+    pub anchor: String,
+    /// What the parser looks for
+    pub expected: String,
+    pub status: AnchorStatus,
+    /// Number of matching elements found
+    pub count: usize,
+    /// HTML of what was found or, when missing, of where it was expected
+    pub excerpts: Vec<String>,
+}
+
+impl AnchorCheck {
+    pub fn new(anchor: &str, expected: impl Into<String>) -> AnchorCheck {
+        AnchorCheck {
+            anchor: anchor.to_string(),
+            expected: expected.into(),
+            status: AnchorStatus::Skipped,
+            count: 0,
+            excerpts: Vec::new(),
+        }
+    }
+
+    /// Marks the anchor found once per element, or ambiguous if `unique` and
+    /// found more than once
+    pub(crate) fn found(mut self, elements: &[ElementRef], unique: bool) -> AnchorCheck {
+        self.count = elements.len();
+        self.status = match (elements.len(), unique) {
+            (0, _) => AnchorStatus::Missing,
+            (1, _) | (_, false) => AnchorStatus::Found,
+            _ => AnchorStatus::Ambiguous,
+        };
+        self.excerpts = elements.iter().take(3).map(excerpt).collect();
+        self
+    }
+
+    /// Marks the anchor missing, with excerpts of where it was looked for
+    pub(crate) fn missing(mut self, looked_in: &[ElementRef]) -> AnchorCheck {
+        self.count = 0;
+        self.status = AnchorStatus::Missing;
+        self.excerpts = looked_in.iter().take(3).map(excerpt).collect();
+        self
+    }
+
+    pub fn is_found(&self) -> bool {
+        self.status == AnchorStatus::Found
+    }
+}
+
+/// Every anchor checked on a page, in the order the parser relies on them
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayoutReport {
+    /// Id of the source whose page was checked
+    pub source: String,
+    pub url: String,
+    pub checks: Vec<AnchorCheck>,
+}
+
+impl LayoutReport {
+    /// Whether every anchor was found as expected
+    pub fn is_healthy(&self) -> bool {
+        self.checks.iter().all(AnchorCheck::is_found)
+    }
+}
+
+impl fmt::Display for LayoutReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} ({})", self.source, self.url)?;
+        for check in &self.checks {
+            writeln!(
+                f,
+                "  [{}] {}: {} found, expected {}",
+                check.status, check.anchor, check.count, check.expected
+            )?;
+            for excerpt in &check.excerpts {
+                writeln!(f, "      {}", excerpt)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The element's HTML on one line, cut short if long
+pub(crate) fn excerpt(element: &ElementRef) -> String {
+    let html = element
+        .html()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    match html.char_indices().nth(EXCERPT_LENGTH) {
+        Some((cut_at, _)) => format!("{}...", &html[..cut_at]),
+        None => html,
+    }
+}
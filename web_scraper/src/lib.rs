@@ -1,6 +1,21 @@
+#[cfg(feature = "async")]
+pub mod async_scraper;
+mod bounded_threads;
+pub mod cassette;
+pub mod company_aliases;
 mod download_manager;
-mod error;
-mod notice_paragraph_parser;
+pub mod error;
+pub mod http_client;
+pub mod layout_report;
+pub mod massachusetts_layout;
+pub mod new_york_source;
+pub mod notice_paragraph_parser;
+pub mod robots;
 pub mod scraper;
 mod scraper_adapter;
+pub mod scraper_config;
+pub mod spreadsheet_source;
+pub mod warn_source;
+mod weekly_report_page;
+mod workbook_pipeline;
 mod year_to_date_xslx_parser;
@@ -0,0 +1,135 @@
+//! The selectors and text anchors the Massachusetts weekly report page is
+//! parsed by. The defaults match the page as currently published; a layout
+//! file overrides any of them, so a renamed label can be fixed without a new
+//! release.
+use std::{fs, path::Path};
+
+use scraper::Selector;
+use serde::Deserialize;
+
+use crate::error::{ScraperError, ScraperResult};
+
+/// Version of the layout file format this build understands
+pub const MASSACHUSETTS_LAYOUT_VERSION: u32 = 1;
+
+/// Labels the fields of a notice start with
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct NoticeLabels {
+    /// Also how notice paragraphs are told apart from the rest of the section
+    pub company: String,
+    pub company_locations: String,
+    pub affected_employees: String,
+    pub effective_date: String,
+    /// Labels of the lines only some notices have
+    pub reason: String,
+    pub notice_type: String,
+    pub union_representation: String,
+    pub contact: String,
+}
+
+impl Default for NoticeLabels {
+    fn default() -> Self {
+        NoticeLabels {
+            company: "Company:".to_string(),
+            company_locations: "Company location(s):".to_string(),
+            affected_employees: "Affected employees:".to_string(),
+            effective_date: "Effective Date:".to_string(),
+            reason: "Reason:".to_string(),
+            notice_type: "Type:".to_string(),
+            union_representation: "Union representation:".to_string(),
+            contact: "Contact:".to_string(),
+        }
+    }
+}
+
+/// Where things are on the Massachusetts weekly report page
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MassachusettsLayout {
+    /// Version of the layout file format
+    pub version: u32,
+    /// Selector of the headings the WARN heading is looked for among
+    pub heading_selector: String,
+    /// Text of the heading the notice section follows
+    pub warn_heading: String,
+    /// Text of the link to the year to date spreadsheet
+    pub yearly_report_text: String,
+    pub labels: NoticeLabels,
+}
+
+impl Default for MassachusettsLayout {
+    fn default() -> Self {
+        MassachusettsLayout {
+            version: MASSACHUSETTS_LAYOUT_VERSION,
+            heading_selector: "body section > h2".to_string(),
+            warn_heading: "Companies that submitted WARN notices this past week".to_string(),
+            yearly_report_text: "WARN Report for the week ending".to_string(),
+            labels: NoticeLabels::default(),
+        }
+    }
+}
+
+impl MassachusettsLayout {
+    /// Reads and validates a layout file
+    pub fn load(path: &Path) -> ScraperResult<MassachusettsLayout> {
+        let layout_text = fs::read_to_string(path)?;
+        Self::parse(&layout_text).map_err(|err| match err {
+            ScraperError::SourceConfig(message) => {
+                ScraperError::SourceConfig(format!("{}: {}", path.display(), message))
+            }
+            err => err,
+        })
+    }
+
+    /// Parses and validates the text of a layout file. Anything left out
+    /// keeps its default.
+    pub fn parse(layout_text: &str) -> ScraperResult<MassachusettsLayout> {
+        let layout: MassachusettsLayout = toml::from_str(layout_text)
+            .map_err(|err| ScraperError::SourceConfig(err.to_string()))?;
+
+        if layout.version > MASSACHUSETTS_LAYOUT_VERSION {
+            return Err(ScraperError::SourceConfig(format!(
+                "layout version {} is newer than the supported version {}",
+                layout.version, MASSACHUSETTS_LAYOUT_VERSION
+            )));
+        }
+        layout.heading_selector()?;
+        let anchors = [
+            ("warn_heading", &layout.warn_heading),
+            ("yearly_report_text", &layout.yearly_report_text),
+            ("labels.company", &layout.labels.company),
+            ("labels.company_locations", &layout.labels.company_locations),
+            (
+                "labels.affected_employees",
+                &layout.labels.affected_employees,
+            ),
+            ("labels.effective_date", &layout.labels.effective_date),
+            ("labels.reason", &layout.labels.reason),
+            ("labels.notice_type", &layout.labels.notice_type),
+            (
+                "labels.union_representation",
+                &layout.labels.union_representation,
+            ),
+            ("labels.contact", &layout.labels.contact),
+        ];
+        if let Some((name, _)) = anchors.iter().find(|(_, text)| text.trim().is_empty()) {
+            return Err(ScraperError::SourceConfig(format!(
+                "{} can't be empty",
+                name
+            )));
+        }
+
+        Ok(layout)
+    }
+
+    /// The heading selector, parsed
+    pub(crate) fn heading_selector(&self) -> ScraperResult<Selector> {
+        Selector::parse(&self.heading_selector).map_err(|err| {
+            ScraperError::SourceConfig(format!(
+                "heading_selector {}: {:?}",
+                self.heading_selector, err
+            ))
+        })
+    }
+}
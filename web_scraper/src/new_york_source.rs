@@ -0,0 +1,286 @@
+//! WARN source for New York State. The Department of Labor lists the current
+//! year's notices on a single page, with each notice linking to its own page.
+//! Notice pages are a series of "Label: value" lines.
+use std::collections::HashMap;
+
+use chrono::{Duration, Local, NaiveDate};
+use proto_generator::notices::{NewYorkNoticeDetails, WARNNotice, WARNNotices};
+use scraper::{ElementRef, Html, Selector};
+use tracing::{debug, info, instrument, warn};
+
+use crate::{
+    bounded_threads::BoundedThreads,
+    error::{ScraperError, ScraperResult},
+    http_client::BlockingHttpClient,
+    scraper::ScraperAdapter,
+    scraper_adapter,
+    scraper_config::ScraperConfig,
+    warn_source::WarnSource,
+};
+
+/// Id the New York source is registered under
+pub const NEW_YORK_SOURCE_ID: &str = "ny";
+pub const NEW_YORK_BASE_URL: &str = "https://dol.ny.gov";
+pub const NEW_YORK_LISTING_URL: &str = "/warn-notices";
+const NEW_YORK_STATE_CODE: &str = "NY";
+
+/// Rows of the listing table. The first cell links to the notice and the
+/// second holds the date of the notice.
+const LISTING_ROW_SELECTOR: &str = "table tbody tr";
+/// Each line of a notice page
+const NOTICE_LINE_SELECTOR: &str = "p";
+/// Several labelled values can share a line, i.e. "County: Albany | Region: Capital"
+const LINE_FIELD_SEPARATOR: char = '|';
+const NOTICE_DATE_FORMAT: &str = "%m/%d/%Y";
+const DAYS_IN_WEEK: i64 = 7;
+
+const DATE_OF_NOTICE_LABEL: &str = "date of warn notice";
+const EVENT_NUMBER_LABEL: &str = "event number";
+const REASON_STATED_LABEL: &str = "reason stated for filing";
+const COMPANY_LABEL: &str = "company";
+const COUNTY_LABEL: &str = "county";
+const REGION_LABEL: &str = "region";
+const BUSINESS_TYPE_LABEL: &str = "business type";
+const NUMBER_AFFECTED_LABEL: &str = "number affected";
+const TOTAL_EMPLOYEES_LABEL: &str = "total employees";
+const LAYOFF_DATE_LABEL: &str = "layoff date";
+const CLOSING_DATE_LABEL: &str = "closing date";
+const REASON_FOR_DISLOCATION_LABEL: &str = "reason for dislocation";
+const UNION_LABEL: &str = "union representation";
+const CLASSIFICATION_LABEL: &str = "classification";
+
+/// A notice as listed on the listing page
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewYorkListingEntry {
+    /// Absolute url of the notice's page
+    pub url: String,
+    pub company: String,
+    pub notice_date: Option<NaiveDate>,
+}
+
+/// Parses the pages published by New York. Kept separate from the fetching so
+/// it can be run against saved pages.
+pub struct NewYorkNoticeParser {}
+
+impl NewYorkNoticeParser {
+    /// # Params
+    /// * listing_html - The listing page
+    /// * base_url - Url relative links on the page are relative to
+    pub fn parse_listing(
+        listing_html: &str,
+        base_url: &str,
+    ) -> ScraperResult<Vec<NewYorkListingEntry>> {
+        let document = Html::parse_document(listing_html);
+        let row_selector = Selector::parse(LISTING_ROW_SELECTOR).unwrap();
+        let cell_selector = Selector::parse("td").unwrap();
+        let link_selector = Selector::parse("a[href]").unwrap();
+
+        document
+            .select(&row_selector)
+            .filter_map(|row| {
+                let cells = row.select(&cell_selector).collect::<Vec<ElementRef>>();
+                let link = cells.first()?.select(&link_selector).next()?;
+                let notice_date = cells.get(1).and_then(|cell| {
+                    NaiveDate::parse_from_str(
+                        scraper_adapter::element_text_to_string(cell).trim(),
+                        NOTICE_DATE_FORMAT,
+                    )
+                    .ok()
+                });
+                Some((link, notice_date))
+            })
+            .map(|(link, notice_date)| {
+                let href = link.value().attr("href").unwrap_or_default();
+                Ok(NewYorkListingEntry {
+                    url: ScraperAdapter::construct_full_url(base_url, href)?,
+                    company: scraper_adapter::element_text_to_string(&link)
+                        .trim()
+                        .to_string(),
+                    notice_date,
+                })
+            })
+            .collect()
+    }
+
+    /// Parses a notice's page into a notice
+    pub fn parse_notice(notice_html: &str) -> ScraperResult<WARNNotice> {
+        let fields = Self::labelled_fields(notice_html);
+        let company_line = fields.get(COMPANY_LABEL).ok_or_else(|| {
+            ScraperError::Parsing("New York notice has no Company line".to_string())
+        })?;
+        let (firm_name, firm_address) = Self::split_company_line(company_line);
+
+        let mut notice = WARNNotice::new();
+        notice.set_firm_name(firm_name);
+        if let Some(firm_address) = firm_address {
+            notice.set_firm_locations(firm_address);
+        }
+        notice.set_state(NEW_YORK_STATE_CODE.to_string());
+
+        let field = |label: &str| fields.get(label).cloned();
+        if let Some(affected) = field(NUMBER_AFFECTED_LABEL) {
+            notice.set_affected_employees(affected);
+        }
+        if let Some(date_received) = field(DATE_OF_NOTICE_LABEL) {
+            notice.set_date_received(date_received);
+        }
+        // A closure only has a closing date
+        if let Some(effective_date) = field(LAYOFF_DATE_LABEL).or(field(CLOSING_DATE_LABEL)) {
+            notice.set_effective_date(effective_date);
+        }
+        if let Some(reason) = field(REASON_FOR_DISLOCATION_LABEL) {
+            notice.set_reason_for_dislocation(reason);
+        }
+
+        let details = NewYorkNoticeDetails {
+            event_number: field(EVENT_NUMBER_LABEL),
+            reason_stated_for_filing: field(REASON_STATED_LABEL),
+            county: field(COUNTY_LABEL),
+            region: field(REGION_LABEL),
+            business_type: field(BUSINESS_TYPE_LABEL),
+            total_employees: field(TOTAL_EMPLOYEES_LABEL),
+            closing_date: field(CLOSING_DATE_LABEL),
+            union_representation: field(UNION_LABEL),
+            classification: field(CLASSIFICATION_LABEL),
+            ..Default::default()
+        };
+        notice.new_york = Some(details).into();
+
+        Ok(notice)
+    }
+
+    /// Every "Label: value" pair on the page, keyed by the lowercase label.
+    /// Lines without a value are skipped.
+    fn labelled_fields(notice_html: &str) -> HashMap<String, String> {
+        let document = Html::parse_document(notice_html);
+        let line_selector = Selector::parse(NOTICE_LINE_SELECTOR).unwrap();
+
+        document
+            .select(&line_selector)
+            .map(|line| scraper_adapter::element_text_to_string(&line))
+            .flat_map(|line| {
+                line.split(LINE_FIELD_SEPARATOR)
+                    .filter_map(|field| {
+                        let (label, value) = field.split_once(':')?;
+                        let value = value.split_whitespace().collect::<Vec<&str>>().join(" ");
+                        match value.is_empty() {
+                            true => None,
+                            false => Some((label.trim().to_lowercase(), value)),
+                        }
+                    })
+                    .collect::<Vec<(String, String)>>()
+            })
+            .collect()
+    }
+
+    /// The company line holds the firm's name followed by its address, i.e.
+    /// "Acme, Inc., 12 Main St, Albany, NY 12207". The address starts at the
+    /// first part beginning with a street number.
+    /// # Return
+    /// The firm name and its address, if one was found
+    fn split_company_line(company_line: &str) -> (String, Option<String>) {
+        let parts = company_line
+            .split(',')
+            .map(str::trim)
+            .collect::<Vec<&str>>();
+
+        match parts
+            .iter()
+            .skip(1)
+            .position(|part| part.starts_with(|c: char| c.is_ascii_digit()))
+        {
+            Some(address_start) => (
+                parts[..=address_start].join(", "),
+                Some(parts[address_start + 1..].join(", ")),
+            ),
+            None => (company_line.trim().to_string(), None),
+        }
+    }
+}
+
+/// Scrapes the notices listed on New York's WARN page
+pub struct NewYorkSource {
+    listing: Vec<NewYorkListingEntry>,
+    client: BlockingHttpClient,
+    /// Most notice pages fetched at once
+    max_concurrent_fetches: usize,
+}
+
+impl NewYorkSource {
+    /// Fetches the listing of this year's notices. Notice pages are only
+    /// fetched once notices are requested.
+    #[instrument(name = "new_york_listing", skip(config))]
+    pub fn new(config: ScraperConfig) -> ScraperResult<NewYorkSource> {
+        let listing_url =
+            ScraperAdapter::construct_full_url(NEW_YORK_BASE_URL, NEW_YORK_LISTING_URL)?;
+        let client = config.blocking_http_client()?;
+        let listing_html = client.get(&listing_url)?.text()?;
+        let listing = NewYorkNoticeParser::parse_listing(&listing_html, NEW_YORK_BASE_URL)?;
+
+        info!(notice_count = listing.len(), "Found New York notices");
+
+        Ok(NewYorkSource {
+            listing,
+            client,
+            max_concurrent_fetches: config.max_concurrent_downloads,
+        })
+    }
+
+    /// Fetches several notice pages at once, keeping the order of the
+    /// listing. Notice pages that can't be fetched or parsed are logged and
+    /// skipped, so one bad page does not lose every other notice.
+    fn fetch_listed_notices<'a, I>(&self, entries: I) -> ScraperResult<WARNNotices>
+    where
+        I: Iterator<Item = &'a NewYorkListingEntry>,
+    {
+        let entries = entries.collect::<Vec<&NewYorkListingEntry>>();
+        let fetched =
+            BoundedThreads::map_ordered(&entries, self.max_concurrent_fetches, |_, entry| {
+                debug!(company = %entry.company, url = %entry.url, "Fetching New York notice");
+                self.fetch_notice(&entry.url)
+            });
+
+        let mut notices = WARNNotices::new();
+        for (entry, notice) in entries.iter().zip(fetched) {
+            match notice {
+                Ok(notice) => notices.notices.push(notice),
+                Err(error) => warn!(
+                    company = %entry.company,
+                    url = %entry.url,
+                    error = %error,
+                    "Skipping New York notice"
+                ),
+            }
+        }
+        Ok(notices)
+    }
+
+    fn fetch_notice(&self, url: &str) -> ScraperResult<WARNNotice> {
+        let notice_html = self.client.get(url)?.text()?;
+        NewYorkNoticeParser::parse_notice(&notice_html)
+    }
+}
+
+impl WarnSource for NewYorkSource {
+    fn id(&self) -> &str {
+        NEW_YORK_SOURCE_ID
+    }
+
+    fn state(&self) -> &str {
+        NEW_YORK_STATE_CODE
+    }
+
+    fn fetch_notices(&self) -> ScraperResult<WARNNotices> {
+        self.fetch_listed_notices(self.listing.iter())
+    }
+
+    /// Notices dated within the past week
+    fn fetch_weekly_notices(&self) -> ScraperResult<WARNNotices> {
+        let week_start = Local::now().date_naive() - Duration::days(DAYS_IN_WEEK);
+        self.fetch_listed_notices(
+            self.listing
+                .iter()
+                .filter(|entry| entry.notice_date.is_some_and(|date| date >= week_start)),
+        )
+    }
+}
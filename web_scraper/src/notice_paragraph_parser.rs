@@ -1,101 +1,330 @@
-//! File with structs used to parse a notice paragraph
-use crate::{
-    error::{ScraperError, ScraperResult},
-    scraper_adapter::{element_text_to_string, ScraperSiblingElement},
-};
+//! File with structs used to parse the notice paragraphs of the weekly report
+//! page. The markup of the paragraphs varies from week to week: fields may
+//! each get an element, share one separated by `<br>`, have their labels
+//! bolded apart from the colon, or be labelled in any case. So rather than
+//! walking elements, the text of the notice section is split into lines and
+//! each line into the fields its labels start. A line starting with a label
+//! no field is parsed from is kept in the notice's `additional_fields`.
+use ego_tree::iter::Edge;
 use proto_generator::notices::WARNNotice;
-pub static INDIVIDUAL_NOTICE_PREFIX: &str = "Company:";
-static COMPANY_LOCATION_LINE_PREFIX: &str = "Company location(s):";
-static AFFECTED_EMPLOYEES_LINE_PREFIX: &str = "Affected employees:";
-static EFFECTIVE_DATE_LINE_PREFIX: &str = "Effective Date:";
-
-/// Struct to parse the paragraph of a notice into a WarnNotice
-pub(crate) struct NoticeParagraphParser<'a> {
-    notice_paragraph_first_sibling: ScraperSiblingElement<'a>,
+use scraper::{ElementRef, Html, Node};
+
+use crate::massachusetts_layout::NoticeLabels;
+
+/// Elements whose start and end break a line of text
+const LINE_BREAKING_ELEMENTS: [&str; 13] = [
+    "br", "p", "div", "li", "tr", "td", "h1", "h2", "h3", "h4", "h5", "h6", "section",
+];
+
+/// Longest label, in characters, a line may start with to be kept in
+/// `additional_fields`
+const MAX_OTHER_LABEL_LENGTH: usize = 40;
+/// Most words such a label may have
+const MAX_OTHER_LABEL_WORDS: usize = 5;
+
+/// A field of a notice, as told by its label
+#[derive(Clone, Debug, PartialEq)]
+enum NoticeField {
+    Company,
+    CompanyLocations,
+    AffectedEmployees,
+    EffectiveDate,
+    Reason,
+    NoticeType,
+    UnionRepresentation,
+    Contact,
+    /// A label no field is parsed from, as written
+    Other(String),
 }
 
-impl<'a> NoticeParagraphParser<'a> {
-    pub(crate) fn new(
-        notice_paragraph_first_sibling: ScraperSiblingElement<'a>,
-    ) -> NoticeParagraphParser {
-        NoticeParagraphParser {
-            notice_paragraph_first_sibling,
-        }
+/// A label as matched against text: its words, lowercased, without the colon
+struct Label {
+    field: NoticeField,
+    words: Vec<Vec<char>>,
+}
+
+/// Parses notice paragraphs into WARNNotices
+pub struct NoticeParagraphParser {
+    /// Longest first, so a label is never matched in place of a longer one
+    /// it starts
+    labels: Vec<Label>,
+}
+
+impl NoticeParagraphParser {
+    /// # Params
+    /// * labels - The labels the fields of a notice start with. Case, the
+    ///   spacing between words and around the colon are ignored.
+    pub fn new(labels: &NoticeLabels) -> NoticeParagraphParser {
+        let mut labels = [
+            (NoticeField::Company, &labels.company),
+            (NoticeField::CompanyLocations, &labels.company_locations),
+            (NoticeField::AffectedEmployees, &labels.affected_employees),
+            (NoticeField::EffectiveDate, &labels.effective_date),
+            (NoticeField::Reason, &labels.reason),
+            (NoticeField::NoticeType, &labels.notice_type),
+            (
+                NoticeField::UnionRepresentation,
+                &labels.union_representation,
+            ),
+            (NoticeField::Contact, &labels.contact),
+        ]
+        .into_iter()
+        .map(|(field, label)| Label {
+            field,
+            words: label
+                .trim()
+                .trim_end_matches(':')
+                .split_whitespace()
+                .map(|word| word.chars().flat_map(char::to_lowercase).collect())
+                .collect(),
+        })
+        .collect::<Vec<Label>>();
+        labels.sort_by_key(|label| std::cmp::Reverse(label.words.concat().len()));
+
+        NoticeParagraphParser { labels }
     }
 
-    /// Parses an individual notice from HTML and produces the protobuf
-    pub(crate) fn parse_notice(&self) -> ScraperResult<WARNNotice> {
-        let mut notice = WARNNotice::new();
+    /// Parses every notice in a fragment of HTML, i.e. a notice section
+    pub fn parse_fragment(&self, html: &str) -> Vec<WARNNotice> {
+        let fragment = Html::parse_fragment(html);
+        self.parse_elements(
+            fragment
+                .root_element()
+                .children()
+                .filter_map(ElementRef::wrap),
+        )
+    }
 
-        for individual_notice in self.notice_paragraph_first_sibling.clone() {
-            match element_text_to_string(&individual_notice).as_str() {
-                company_line if company_line.contains(INDIVIDUAL_NOTICE_PREFIX) => {
-                    notice.set_firm_name(self.parse_company_line(company_line)?)
-                }
-                location_line if location_line.contains(COMPANY_LOCATION_LINE_PREFIX) => {
-                    notice.set_firm_locations(self.parse_location(location_line)?)
-                }
-                affected_employees_line
-                    if affected_employees_line.contains(AFFECTED_EMPLOYEES_LINE_PREFIX) =>
-                {
-                    notice.set_affected_employees(self.parse_affected(affected_employees_line)?)
+    /// # Brief
+    /// Parses the notices of a run of sibling elements. Each company label
+    /// starts a notice, which takes every field up to the next company label
+    /// or the next element with text but no label.
+    pub(crate) fn parse_elements<'a, I>(&self, elements: I) -> Vec<WARNNotice>
+    where
+        I: IntoIterator<Item = ElementRef<'a>>,
+    {
+        let mut notices = Vec::new();
+        let mut current_notice: Option<WARNNotice> = None;
+
+        for element in elements {
+            let lines = Self::element_lines(element);
+            // Spacers don't end a notice
+            if lines.is_empty() {
+                continue;
+            }
+            let fields = self.tokenise(&lines);
+            if fields.is_empty() {
+                notices.extend(current_notice.take());
+                continue;
+            }
+
+            for (field, value) in fields {
+                if field == NoticeField::Company {
+                    notices.extend(current_notice.take());
+                    current_notice = Some(WARNNotice::new());
                 }
-                effective_date_line if effective_date_line.contains(EFFECTIVE_DATE_LINE_PREFIX) => {
-                    notice.set_effective_date(self.parse_effective_date(effective_date_line)?)
+                if let Some(notice) = current_notice.as_mut() {
+                    Self::set_field(notice, field, value);
                 }
-                _ => {}
             }
         }
+        notices.extend(current_notice);
 
-        Ok(notice)
+        notices
     }
 
-    /// Parses the company line and produces the firm_name string for the proto
-    fn parse_company_line(&self, raw_company_line: &str) -> ScraperResult<String> {
-        Self::general_parse(
-            raw_company_line,
-            INDIVIDUAL_NOTICE_PREFIX,
-            "Error getting company from splitting company line".to_string(),
-        )
+    /// Whether the element holds the start of a notice
+    pub(crate) fn is_notice(&self, element: ElementRef) -> bool {
+        self.tokenise(&Self::element_lines(element))
+            .iter()
+            .any(|(field, _)| *field == NoticeField::Company)
     }
 
-    fn parse_location(&self, raw_locations_line: &str) -> ScraperResult<String> {
-        Self::general_parse(
-            raw_locations_line,
-            COMPANY_LOCATION_LINE_PREFIX,
-            "Error getting location from splitting location line".to_string(),
-        )
+    fn set_field(notice: &mut WARNNotice, field: NoticeField, value: String) {
+        match field {
+            NoticeField::Company => notice.set_firm_name(value),
+            NoticeField::CompanyLocations => notice.set_firm_locations(value),
+            NoticeField::AffectedEmployees => notice.set_affected_employees(value),
+            NoticeField::EffectiveDate => notice.set_effective_date(value),
+            NoticeField::Reason => notice.set_reason_for_dislocation(value),
+            NoticeField::NoticeType => notice.set_notice_type(value),
+            NoticeField::UnionRepresentation => notice.set_union_representation(value),
+            NoticeField::Contact => notice.set_contact(value),
+            NoticeField::Other(label) => {
+                notice.additional_fields.insert(label, value);
+            }
+        }
     }
 
-    fn parse_affected(&self, raw_affected_line: &str) -> ScraperResult<String> {
-        Self::general_parse(
-            raw_affected_line,
-            AFFECTED_EMPLOYEES_LINE_PREFIX,
-            "Error getting location from splitting affected employees line".to_string(),
-        )
+    /// # Brief
+    /// Splits the text of an element into lines where the rendered page
+    /// would, collapsing whitespace.
+    /// # Return
+    /// The non empty lines
+    fn element_lines(element: ElementRef) -> Vec<String> {
+        let mut lines = vec![String::new()];
+        for edge in element.traverse() {
+            match edge {
+                Edge::Open(node) => match node.value() {
+                    Node::Text(text) => {
+                        if let Some(line) = lines.last_mut() {
+                            line.push_str(text)
+                        }
+                    }
+                    Node::Element(el) if LINE_BREAKING_ELEMENTS.contains(&el.name()) => {
+                        lines.push(String::new())
+                    }
+                    _ => {}
+                },
+                Edge::Close(node) => {
+                    if let Node::Element(el) = node.value() {
+                        if LINE_BREAKING_ELEMENTS.contains(&el.name()) {
+                            lines.push(String::new())
+                        }
+                    }
+                }
+            }
+        }
+
+        lines
+            .iter()
+            .map(|line| line.split_whitespace().collect::<Vec<&str>>().join(" "))
+            .filter(|line| !line.is_empty())
+            .collect()
     }
 
-    fn parse_effective_date(&self, raw_date_line: &str) -> ScraperResult<String> {
-        Self::general_parse(
-            raw_date_line,
-            EFFECTIVE_DATE_LINE_PREFIX,
-            "Error getting location from splitting effective date line".to_string(),
-        )
+    /// # Brief
+    /// Splits lines into the fields their labels start. A field's value runs
+    /// to the next label or the end of the line; a label ending its line
+    /// takes the next line as its value, unless that starts with a label.
+    fn tokenise(&self, lines: &[String]) -> Vec<(NoticeField, String)> {
+        let mut fields: Vec<(NoticeField, String)> = Vec::new();
+        let mut awaiting_value: Option<NoticeField> = None;
+
+        for line in lines {
+            let chars = line.chars().collect::<Vec<char>>();
+            let mut labels = self.find_labels(&chars);
+
+            let unlabelled_end = labels.first().map_or(chars.len(), |(start, _, _)| *start);
+            if unlabelled_end > 0 {
+                match Self::match_other_label(&chars[..unlabelled_end]) {
+                    Some((label, value_start)) => {
+                        labels.insert(0, (0, value_start, NoticeField::Other(label)))
+                    }
+                    None => {
+                        if let Some(field) = awaiting_value.take() {
+                            let value = chars[..unlabelled_end].iter().collect::<String>();
+                            fields.push((field, value.trim().to_string()));
+                        }
+                    }
+                }
+            }
+            // A label left without a value keeps its field, empty
+            fields.extend(awaiting_value.take().map(|field| (field, String::new())));
+
+            for (idx, (_, value_start, field)) in labels.iter().enumerate() {
+                let value_end = labels
+                    .get(idx + 1)
+                    .map_or(chars.len(), |(next_start, _, _)| *next_start);
+                let value = chars[*value_start..value_end]
+                    .iter()
+                    .collect::<String>()
+                    .trim()
+                    .to_string();
+
+                match value.is_empty() && idx == labels.len() - 1 {
+                    true => awaiting_value = Some(field.clone()),
+                    false => fields.push((field.clone(), value)),
+                }
+            }
+        }
+        fields.extend(awaiting_value.map(|field| (field, String::new())));
+
+        fields
     }
 
-    /// # Params
-    /// * raw_line raw - line to parse
-    /// * line_prefix - expected prefix for the line to parse
-    /// * err_msg - The error message to use if the parsing fails
-    fn general_parse(raw_line: &str, line_prefix: &str, err_msg: String) -> ScraperResult<String> {
-        let parsed_line = raw_line
-            .trim()
-            .split(line_prefix)
-            .nth(1)
-            .ok_or_else(|| ScraperError::Parsing(err_msg))?
-            .trim()
-            .to_string();
-
-        Ok(parsed_line)
+    /// # Return
+    /// Where each label in the line starts, where its value starts and the
+    /// field it labels, in order
+    fn find_labels(&self, chars: &[char]) -> Vec<(usize, usize, NoticeField)> {
+        let mut found = Vec::new();
+        let mut idx = 0;
+        while idx < chars.len() {
+            let matched = self.labels.iter().find_map(|label| {
+                Self::match_label(chars, idx, &label.words).map(|end| (end, label.field.clone()))
+            });
+            match matched {
+                Some((value_start, field)) => {
+                    found.push((idx, value_start, field));
+                    idx = value_start;
+                }
+                None => idx += 1,
+            }
+        }
+        found
+    }
+
+    /// # Brief
+    /// Matches a label no field is parsed from at the start of a line: a few
+    /// words starting with a letter, then a colon followed by a space or the
+    /// end of the line, so the colon of a time or url is never taken for one.
+    /// # Return
+    /// The label without the colon, and where the text after it starts
+    fn match_other_label(chars: &[char]) -> Option<(String, usize)> {
+        let colon = chars.iter().position(|c| *c == ':')?;
+        if chars.get(colon + 1).is_some_and(|c| !c.is_whitespace()) {
+            return None;
+        }
+
+        let label = chars[..colon].iter().collect::<String>().trim().to_string();
+        let is_label = label.chars().next().is_some_and(char::is_alphabetic)
+            && label.chars().count() <= MAX_OTHER_LABEL_LENGTH
+            && label.split_whitespace().count() <= MAX_OTHER_LABEL_WORDS;
+        match is_label {
+            true => Some((label, colon + 1)),
+            false => None,
+        }
+    }
+
+    /// # Brief
+    /// Matches the words of a label, then a colon, at the start of a word.
+    /// Words may be separated by any whitespace, and the colon preceded by
+    /// some.
+    /// # Return
+    /// Where the text after the colon starts, if the label matched
+    fn match_label(chars: &[char], start: usize, words: &[Vec<char>]) -> Option<usize> {
+        if words.is_empty() || (start > 0 && chars[start - 1].is_alphanumeric()) {
+            return None;
+        }
+
+        let mut idx = start;
+        for (word_idx, word) in words.iter().enumerate() {
+            if word_idx > 0 {
+                let spaces = chars[idx..]
+                    .iter()
+                    .take_while(|c| c.is_whitespace())
+                    .count();
+                if spaces == 0 {
+                    return None;
+                }
+                idx += spaces;
+            }
+            for expected in word {
+                let actual = chars.get(idx)?;
+                if !actual.to_lowercase().eq(expected.to_lowercase()) {
+                    return None;
+                }
+                idx += 1;
+            }
+        }
+
+        idx += chars[idx..]
+            .iter()
+            .take_while(|c| c.is_whitespace())
+            .count();
+        match chars.get(idx) {
+            Some(':') => Some(idx + 1),
+            _ => None,
+        }
     }
 }
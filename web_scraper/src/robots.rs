@@ -0,0 +1,193 @@
+//! robots.txt parsing and matching, following RFC 9309. The HTTP clients
+//! check every url against the robots.txt of its host before requesting it.
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use reqwest::StatusCode;
+
+/// An allow or disallow line
+#[derive(Clone, Debug, PartialEq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// Rules applying to the user agents listed above them
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Group {
+    user_agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+/// The rules of a host's robots.txt
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RobotsTxt {
+    groups: Vec<Group>,
+}
+
+impl RobotsTxt {
+    /// Rules letting every crawler fetch everything
+    pub fn allow_all() -> RobotsTxt {
+        RobotsTxt::default()
+    }
+
+    /// Rules forbidding every crawler from fetching anything
+    pub fn disallow_all() -> RobotsTxt {
+        RobotsTxt::parse("User-agent: *\nDisallow: /")
+    }
+
+    /// # Brief
+    /// What to assume when a host's robots.txt could not be fetched. Per the
+    /// RFC a missing robots.txt allows everything, while a server error
+    /// means the host is not to be crawled at all.
+    /// # Return
+    /// None when the status is not an error
+    pub fn unavailable(status: StatusCode) -> Option<RobotsTxt> {
+        match status {
+            status if status.is_client_error() => Some(RobotsTxt::allow_all()),
+            status if status.is_server_error() => Some(RobotsTxt::disallow_all()),
+            _ => None,
+        }
+    }
+
+    /// Parses a robots.txt. Lines that are not understood are ignored.
+    pub fn parse(robots_txt: &str) -> RobotsTxt {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_rules = false;
+
+        for line in robots_txt.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            match key.trim().to_lowercase().as_str() {
+                "user-agent" => {
+                    // Consecutive user agent lines share the rules that follow
+                    if in_rules || groups.is_empty() {
+                        groups.push(Group::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.user_agents.push(value.to_lowercase());
+                    }
+                }
+                key @ ("allow" | "disallow") => {
+                    in_rules = true;
+                    // An empty disallow allows everything, which is the default
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        RobotsTxt { groups }
+    }
+
+    /// # Params
+    /// * user_agent - User agent of the crawler, i.e. "WARN-Scraper/0.1.0".
+    ///   Only the product token before the first '/' is matched.
+    /// * path - Path of the url to fetch, including its query
+    /// # Return
+    /// Whether the crawler may fetch the path
+    pub fn is_allowed(&self, user_agent: &str, path: &str) -> bool {
+        if path == "/robots.txt" {
+            return true;
+        }
+
+        let product = Self::product_token(user_agent);
+        let matching_groups = |agent: &str| {
+            self.groups
+                .iter()
+                .filter(|group| group.user_agents.iter().any(|a| a == agent))
+                .collect::<Vec<&Group>>()
+        };
+        let mut groups = matching_groups(&product);
+        if groups.is_empty() {
+            groups = matching_groups("*");
+        }
+
+        // The most specific matching rule wins, allow winning ties
+        groups
+            .iter()
+            .flat_map(|group| &group.rules)
+            .filter(|rule| Self::matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+
+    fn product_token(user_agent: &str) -> String {
+        user_agent
+            .split(['/', ' '])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    }
+
+    /// Matches a path against a rule's pattern, where '*' matches any run of
+    /// characters and a trailing '$' anchors the pattern to the end of the path
+    fn matches(pattern: &str, path: &str) -> bool {
+        let (pattern, anchored) = match pattern.strip_suffix('$') {
+            Some(pattern) => (pattern, true),
+            None => (pattern, false),
+        };
+        let mut parts = pattern.split('*');
+        let Some(rest) = parts.next().and_then(|prefix| path.strip_prefix(prefix)) else {
+            return false;
+        };
+
+        let mut rest = rest;
+        let parts: Vec<&str> = parts.collect();
+        for (idx, part) in parts.iter().enumerate() {
+            let is_last = idx == parts.len() - 1;
+            if is_last && anchored {
+                return rest.ends_with(part);
+            }
+            match rest.find(part) {
+                Some(found) => rest = &rest[found + part.len()..],
+                None => return false,
+            }
+        }
+
+        !anchored || rest.is_empty()
+    }
+}
+
+/// robots.txt rules fetched so far, by origin. Clones share their entries.
+#[derive(Clone, Default)]
+pub struct RobotsCache {
+    robots_txts: Arc<Mutex<HashMap<String, Arc<RobotsTxt>>>>,
+}
+
+impl fmt::Debug for RobotsCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RobotsCache").finish_non_exhaustive()
+    }
+}
+
+impl RobotsCache {
+    pub(crate) fn get(&self, origin: &str) -> Option<Arc<RobotsTxt>> {
+        self.robots_txts.lock().unwrap().get(origin).cloned()
+    }
+
+    pub(crate) fn insert(&self, origin: &str, robots_txt: RobotsTxt) -> Arc<RobotsTxt> {
+        let robots_txt = Arc::new(robots_txt);
+        self.robots_txts
+            .lock()
+            .unwrap()
+            .insert(origin.to_string(), robots_txt.clone());
+        robots_txt
+    }
+}
@@ -16,7 +16,7 @@ pub(crate) struct ScraperSiblingElement<'a> {
 impl<'a> ScraperSiblingElement<'a> {
     /// Given a sibling, provides an Element iterator over siblings at the same
     /// level of HTML tree
-    pub(crate) fn new(initial_sibling: ElementRef<'a>) -> ScraperSiblingElement {
+    pub(crate) fn new(initial_sibling: ElementRef<'a>) -> ScraperSiblingElement<'a> {
         ScraperSiblingElement {
             current_sibling: Some(initial_sibling),
         }
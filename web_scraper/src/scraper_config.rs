@@ -0,0 +1,226 @@
+//! Settings controlling how sources fetch their pages and spreadsheets
+use std::{path::PathBuf, time::Duration};
+
+#[cfg(feature = "async")]
+use crate::http_client::HttpClient;
+use crate::{
+    cassette::Cassette,
+    error::ScraperResult,
+    http_client::{BlockingHttpClient, RateLimiter},
+    massachusetts_layout::MassachusettsLayout,
+    robots::RobotsCache,
+};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_USER_AGENT: &str = concat!("WARN-Scraper/", env!("CARGO_PKG_VERSION"));
+pub const DEFAULT_DOWNLOAD_DIRECTORY: &str = "/tmp/WARN_Reports/";
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+pub const DEFAULT_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+pub const DEFAULT_MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+pub const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 4;
+
+/// How sources fetch what they scrape. Create one with `ScraperConfig::builder()`
+/// or use the defaults.
+#[derive(Clone, Debug)]
+pub struct ScraperConfig {
+    /// Longest a single request may take, connecting included
+    pub timeout: Duration,
+    /// Sent with every request. Its product token, the part before the
+    /// first '/', is what robots.txt rules are matched against.
+    pub user_agent: String,
+    /// Email or url site owners can reach us at, appended to the user agent
+    pub contact: Option<String>,
+    /// Whether urls disallowed by their host's robots.txt are refused
+    pub respect_robots_txt: bool,
+    /// Proxy every request is sent through, i.e. "http://proxy.corp:3128".
+    /// When unset the HTTP_PROXY, HTTPS_PROXY and NO_PROXY environment
+    /// variables are used.
+    pub proxy: Option<String>,
+    /// Hosts and domains requested directly rather than through the proxy,
+    /// including one taken from the environment
+    pub no_proxy: Vec<String>,
+    /// PEM files of root certificates to trust on top of the system's, i.e.
+    /// that of an intercepting proxy
+    pub root_certificates: Vec<PathBuf>,
+    /// Spreadsheets are downloaded into a subdirectory of this per source,
+    /// and deleted once parsed
+    pub download_directory: PathBuf,
+    /// Whether the Massachusetts year to date spreadsheet is downloaded on top
+    /// of the weekly page when fetching every notice
+    pub include_year_to_date: bool,
+    /// Times a failed request is retried before giving up. Connection errors,
+    /// timeouts, server errors and 429s are retried.
+    pub max_retries: u32,
+    /// Wait before the first retry. Each retry waits about twice as long as
+    /// the last, give or take some jitter.
+    pub retry_delay: Duration,
+    /// Longest wait between retries. Servers asking for a longer wait through
+    /// Retry-After are given up on instead.
+    pub max_retry_delay: Duration,
+    /// Shortest time between the start of two requests to the same host
+    pub min_request_interval: Duration,
+    /// Most workbooks, or New York notice pages, a source downloads at once
+    pub max_concurrent_downloads: usize,
+    /// Massachusetts year to date workbooks of earlier years, merged after
+    /// the current one when fetching every notice
+    pub archive_workbook_urls: Vec<String>,
+    /// Selectors and text anchors the Massachusetts page is parsed by
+    pub massachusetts_layout: MassachusettsLayout,
+    /// Records every response to a directory, or replays them from one
+    /// instead of hitting the network
+    pub cassette: Option<Cassette>,
+    /// Shared by every clone of the config, so sources created from the same
+    /// config share a schedule per host
+    pub(crate) rate_limiter: RateLimiter,
+    /// Shared like the rate limiter, so each robots.txt is fetched once
+    pub(crate) robots_cache: RobotsCache,
+}
+
+impl Default for ScraperConfig {
+    fn default() -> Self {
+        ScraperConfig {
+            timeout: DEFAULT_TIMEOUT,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            contact: None,
+            respect_robots_txt: true,
+            proxy: None,
+            no_proxy: Vec::new(),
+            root_certificates: Vec::new(),
+            download_directory: PathBuf::from(DEFAULT_DOWNLOAD_DIRECTORY),
+            include_year_to_date: true,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+            max_retry_delay: DEFAULT_MAX_RETRY_DELAY,
+            min_request_interval: DEFAULT_MIN_REQUEST_INTERVAL,
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            archive_workbook_urls: Vec::new(),
+            massachusetts_layout: MassachusettsLayout::default(),
+            cassette: None,
+            rate_limiter: RateLimiter::default(),
+            robots_cache: RobotsCache::default(),
+        }
+    }
+}
+
+impl ScraperConfig {
+    pub fn builder() -> ScraperConfigBuilder {
+        ScraperConfigBuilder::default()
+    }
+
+    /// The user agent header sent, i.e. "WARN-Scraper/0.1.0 (+ops@example.com)"
+    pub fn user_agent_header(&self) -> String {
+        match &self.contact {
+            Some(contact) => format!("{} (+{})", self.user_agent, contact),
+            None => self.user_agent.clone(),
+        }
+    }
+
+    /// A client fetching as configured, for async code
+    #[cfg(feature = "async")]
+    pub fn http_client(&self) -> ScraperResult<HttpClient> {
+        HttpClient::new(self)
+    }
+
+    /// A client fetching as configured, for blocking code
+    pub fn blocking_http_client(&self) -> ScraperResult<BlockingHttpClient> {
+        BlockingHttpClient::new(self)
+    }
+}
+
+/// Builds a `ScraperConfig`, starting from the defaults
+#[derive(Default)]
+pub struct ScraperConfigBuilder {
+    config: ScraperConfig,
+}
+
+impl ScraperConfigBuilder {
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.config.user_agent = user_agent.into();
+        self
+    }
+
+    pub fn contact(mut self, contact: impl Into<String>) -> Self {
+        self.config.contact = Some(contact.into());
+        self
+    }
+
+    pub fn respect_robots_txt(mut self, respect_robots_txt: bool) -> Self {
+        self.config.respect_robots_txt = respect_robots_txt;
+        self
+    }
+
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.config.proxy = Some(proxy.into());
+        self
+    }
+
+    pub fn no_proxy(mut self, no_proxy: Vec<String>) -> Self {
+        self.config.no_proxy = no_proxy;
+        self
+    }
+
+    pub fn root_certificates(mut self, root_certificates: Vec<PathBuf>) -> Self {
+        self.config.root_certificates = root_certificates;
+        self
+    }
+
+    pub fn download_directory(mut self, download_directory: impl Into<PathBuf>) -> Self {
+        self.config.download_directory = download_directory.into();
+        self
+    }
+
+    pub fn include_year_to_date(mut self, include_year_to_date: bool) -> Self {
+        self.config.include_year_to_date = include_year_to_date;
+        self
+    }
+
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.config.max_retries = max_retries;
+        self
+    }
+
+    pub fn retry_delay(mut self, retry_delay: Duration) -> Self {
+        self.config.retry_delay = retry_delay;
+        self
+    }
+
+    pub fn max_retry_delay(mut self, max_retry_delay: Duration) -> Self {
+        self.config.max_retry_delay = max_retry_delay;
+        self
+    }
+
+    pub fn min_request_interval(mut self, min_request_interval: Duration) -> Self {
+        self.config.min_request_interval = min_request_interval;
+        self
+    }
+
+    pub fn max_concurrent_downloads(mut self, max_concurrent_downloads: usize) -> Self {
+        self.config.max_concurrent_downloads = max_concurrent_downloads;
+        self
+    }
+
+    pub fn archive_workbook_urls(mut self, archive_workbook_urls: Vec<String>) -> Self {
+        self.config.archive_workbook_urls = archive_workbook_urls;
+        self
+    }
+
+    pub fn massachusetts_layout(mut self, massachusetts_layout: MassachusettsLayout) -> Self {
+        self.config.massachusetts_layout = massachusetts_layout;
+        self
+    }
+
+    pub fn cassette(mut self, cassette: Cassette) -> Self {
+        self.config.cassette = Some(cassette);
+        self
+    }
+
+    pub fn build(self) -> ScraperConfig {
+        self.config
+    }
+}
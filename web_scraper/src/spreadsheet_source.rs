@@ -0,0 +1,404 @@
+//! WARN source for states that publish their notices as a single xlsx or csv
+//! spreadsheet. Where the spreadsheet lives and how its columns map onto
+//! notice fields is described by a config file, so supporting such a state
+//! does not need any new code.
+//!
+//! A config is a TOML file such as:
+//! ```toml
+//! id = "wa"
+//! name = "Washington"
+//! state = "WA"
+//! url = "https://example.gov/warn/notices.xlsx"
+//! # Spreadsheets of earlier years, in the same layout
+//! archive_urls = ["https://example.gov/warn/notices_2023.xlsx"]
+//! # Only read this sheet. Every sheet is read when left out.
+//! sheet = "WARN Notices"
+//! # Row the column headings are on, counting from 1
+//! header_row = 1
+//! # Formats dates in the spreadsheet may be written in
+//! date_formats = ["%m/%d/%Y"]
+//!
+//! # Heading of the column each field is read from
+//! [columns]
+//! firm_name = "Company"
+//! firm_locations = "Location"
+//! affected_employees = "# of Workers"
+//! effective_date = "Layoff Start Date"
+//! date_received = "Received Date"
+//! reason_for_dislocation = "Layoff Type"
+//! ```
+use std::{fs, path::Path};
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use office::{DataType, Excel};
+use proto_generator::notices::{WARNNotice, WARNNotices};
+use serde::Deserialize;
+use tracing::{info, info_span};
+
+use crate::{
+    error::{ScraperError, ScraperResult},
+    scraper_config::ScraperConfig,
+    warn_source::WarnSource,
+    workbook_pipeline::{BlockingWorkbookPipeline, WorkbookDownload},
+    year_to_date_xslx_parser::YearToDateParser,
+};
+
+const DAYS_IN_WEEK: i64 = 7;
+/// Format dates are stored on notices in, matching the year to date parser
+const NOTICE_DATE_FORMAT: &str = "%Y-%m-%d";
+/// Dates parsed before this year came from a format that did not fit
+const MIN_NOTICE_YEAR: i32 = 1900;
+
+/// Kind of file a spreadsheet source publishes
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SpreadsheetFormat {
+    Xlsx,
+    Csv,
+}
+
+/// Heading of the column each notice field is read from. Fields without a
+/// column are left unset. Headings are matched ignoring case and surrounding
+/// whitespace.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpreadsheetColumns {
+    pub firm_name: Option<String>,
+    pub firm_locations: Option<String>,
+    pub affected_employees: Option<String>,
+    pub effective_date: Option<String>,
+    pub date_received: Option<String>,
+    pub reason_for_dislocation: Option<String>,
+    /// Column stating whether the notice is of a closure, a layoff or a
+    /// relocation
+    pub notice_type: Option<String>,
+}
+
+/// Describes a state publishing its notices as a spreadsheet
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpreadsheetSourceConfig {
+    /// Short id the source is selected by, i.e. "wa"
+    pub id: String,
+    /// Human readable name of the source
+    pub name: String,
+    /// Two letter code of the state
+    pub state: String,
+    /// Where the spreadsheet is downloaded from
+    pub url: String,
+    /// Spreadsheets of earlier years in the same layout, merged after the
+    /// current one
+    #[serde(default)]
+    pub archive_urls: Vec<String>,
+    /// Defaults to the extension of the url, or xlsx when it has none
+    pub format: Option<SpreadsheetFormat>,
+    /// Sheet to read from an xlsx workbook. Every sheet is read when unset.
+    pub sheet: Option<String>,
+    /// Row the column headings are on, counting from 1. Notices are read from
+    /// every row after it.
+    #[serde(default = "SpreadsheetSourceConfig::default_header_row")]
+    pub header_row: usize,
+    /// chrono formats dates written as text may be in, tried in order. Dates
+    /// matching none of them are kept as written.
+    #[serde(default)]
+    pub date_formats: Vec<String>,
+    pub columns: SpreadsheetColumns,
+}
+
+impl SpreadsheetSourceConfig {
+    /// Reads and validates a config file
+    pub fn load(path: &Path) -> ScraperResult<SpreadsheetSourceConfig> {
+        let config_text = fs::read_to_string(path)?;
+        Self::parse(&config_text).map_err(|err| match err {
+            ScraperError::SourceConfig(message) => {
+                ScraperError::SourceConfig(format!("{}: {}", path.display(), message))
+            }
+            err => err,
+        })
+    }
+
+    /// Parses and validates the text of a config file
+    pub fn parse(config_text: &str) -> ScraperResult<SpreadsheetSourceConfig> {
+        let config: SpreadsheetSourceConfig = toml::from_str(config_text)
+            .map_err(|err| ScraperError::SourceConfig(err.to_string()))?;
+
+        if config.header_row == 0 {
+            return Err(ScraperError::SourceConfig(
+                "header_row counts from 1".to_string(),
+            ));
+        }
+        if config.columns.firm_name.is_none() {
+            return Err(ScraperError::SourceConfig(
+                "columns.firm_name is required".to_string(),
+            ));
+        }
+
+        Ok(config)
+    }
+
+    /// The configured format, or the one the url's extension implies
+    pub fn format(&self) -> SpreadsheetFormat {
+        self.format.unwrap_or_else(|| {
+            match self
+                .url
+                .to_lowercase()
+                .trim_end_matches('/')
+                .ends_with(".csv")
+            {
+                true => SpreadsheetFormat::Csv,
+                false => SpreadsheetFormat::Xlsx,
+            }
+        })
+    }
+
+    fn default_header_row() -> usize {
+        1
+    }
+}
+
+/// Converts the rows of a spreadsheet into notices as described by a config
+pub struct SpreadsheetParser<'a> {
+    config: &'a SpreadsheetSourceConfig,
+}
+
+impl<'a> SpreadsheetParser<'a> {
+    pub fn new(config: &'a SpreadsheetSourceConfig) -> SpreadsheetParser<'a> {
+        SpreadsheetParser { config }
+    }
+
+    /// Parses a downloaded spreadsheet in the config's format
+    pub fn parse_file(&self, path: &Path) -> ScraperResult<WARNNotices> {
+        let sheets = match self.config.format() {
+            SpreadsheetFormat::Xlsx => Self::read_xlsx(path, self.config.sheet.as_deref())?,
+            SpreadsheetFormat::Csv => vec![Self::read_csv(&fs::read(path)?)?],
+        };
+
+        let mut notices = WARNNotices::new();
+        for rows in sheets {
+            notices.notices.append(&mut self.parse_rows(&rows)?.notices);
+        }
+        Ok(notices)
+    }
+
+    /// # Params
+    /// * rows - Every row of a sheet, including those before the headings
+    pub fn parse_rows(&self, rows: &[Vec<DataType>]) -> ScraperResult<WARNNotices> {
+        let header_idx = self.config.header_row - 1;
+        let headings = rows.get(header_idx).ok_or_else(|| {
+            ScraperError::ExcelParsing(format!(
+                "Spreadsheet for {} has no heading row {}",
+                self.config.id, self.config.header_row
+            ))
+        })?;
+
+        let columns = &self.config.columns;
+        let column_idx = |heading: &Option<String>| -> ScraperResult<Option<usize>> {
+            heading
+                .as_ref()
+                .map(|heading| Self::find_column(headings, heading))
+                .transpose()
+        };
+        let firm_name_idx = column_idx(&columns.firm_name)?;
+        let firm_locations_idx = column_idx(&columns.firm_locations)?;
+        let affected_employees_idx = column_idx(&columns.affected_employees)?;
+        let effective_date_idx = column_idx(&columns.effective_date)?;
+        let date_received_idx = column_idx(&columns.date_received)?;
+        let reason_idx = column_idx(&columns.reason_for_dislocation)?;
+        let notice_type_idx = column_idx(&columns.notice_type)?;
+
+        let mut notices = WARNNotices::new();
+        for row in rows.iter().skip(header_idx + 1) {
+            let cell = |idx: Option<usize>| idx.and_then(|idx| row.get(idx));
+
+            let notice = WARNNotice {
+                firm_name: cell(firm_name_idx).and_then(Self::cell_text),
+                firm_locations: cell(firm_locations_idx).and_then(Self::cell_text),
+                affected_employees: cell(affected_employees_idx).and_then(Self::cell_text),
+                effective_date: cell(effective_date_idx).and_then(|c| self.cell_date(c)),
+                date_received: cell(date_received_idx).and_then(|c| self.cell_date(c)),
+                reason_for_dislocation: cell(reason_idx).and_then(Self::cell_text),
+                notice_type: cell(notice_type_idx).and_then(Self::cell_text),
+                state: Some(self.config.state.clone()),
+                ..::std::default::Default::default()
+            };
+
+            // Spreadsheets often end with blank or total rows
+            if notice.has_firm_name() {
+                notices.notices.push(notice);
+            }
+        }
+
+        Ok(notices)
+    }
+
+    fn find_column(headings: &[DataType], heading: &str) -> ScraperResult<usize> {
+        headings
+            .iter()
+            .position(|cell| {
+                Self::cell_text(cell).is_some_and(|text| text.eq_ignore_ascii_case(heading.trim()))
+            })
+            .ok_or_else(|| {
+                ScraperError::ExcelParsing(format!("No column with the heading {}", heading))
+            })
+    }
+
+    /// Text of a cell, with surrounding whitespace removed
+    /// # Return
+    /// None if the cell is empty, an error or a boolean
+    fn cell_text(cell: &DataType) -> Option<String> {
+        let text = match cell {
+            DataType::Int(value) => value.to_string(),
+            DataType::Float(value) => value.to_string(),
+            DataType::String(value) => value.trim().to_string(),
+            DataType::Bool(_) | DataType::Empty | DataType::Error(_) => return None,
+        };
+        match text.is_empty() {
+            true => None,
+            false => Some(text),
+        }
+    }
+
+    /// # Brief
+    /// Dates stored as numbers are days since 1900, as in the year to date
+    /// spreadsheet. Dates stored as text are converted when they match one of
+    /// the configured formats and kept as written otherwise.
+    fn cell_date(&self, cell: &DataType) -> Option<String> {
+        let date = match cell {
+            DataType::Int(value) => YearToDateParser::from_days_since_1900(*value),
+            DataType::Float(value) => YearToDateParser::from_days_since_1900(*value as i64),
+            _ => {
+                let text = Self::cell_text(cell)?;
+                return Some(
+                    self.config
+                        .date_formats
+                        .iter()
+                        .find_map(|format| {
+                            NaiveDate::parse_from_str(&text, format)
+                                .ok()
+                                // %Y happily reads the 24 of 1/5/24 as the year 24
+                                .filter(|date| date.year() >= MIN_NOTICE_YEAR)
+                        })
+                        .map(|date| date.format(NOTICE_DATE_FORMAT).to_string())
+                        .unwrap_or(text),
+                );
+            }
+        };
+        Some(date.format(NOTICE_DATE_FORMAT).to_string())
+    }
+
+    /// # Return
+    /// The rows of the named sheet, or of every sheet when none is named
+    fn read_xlsx(path: &Path, sheet: Option<&str>) -> ScraperResult<Vec<Vec<Vec<DataType>>>> {
+        let excel_err = |err: office::Error| ScraperError::ExcelParsing(err.to_string());
+        let mut workbook = Excel::open(path).map_err(excel_err)?;
+
+        let sheet_names = match sheet {
+            Some(sheet) => vec![sheet.to_string()],
+            None => workbook.sheet_names().map_err(excel_err)?,
+        };
+
+        sheet_names
+            .iter()
+            .map(|sheet_name| {
+                let range = workbook.worksheet_range(sheet_name).map_err(excel_err)?;
+                Ok(range.rows().map(|row| row.to_vec()).collect())
+            })
+            .collect()
+    }
+
+    /// Every cell of a csv file is read as text
+    fn read_csv(csv_bytes: &[u8]) -> ScraperResult<Vec<Vec<DataType>>> {
+        csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(csv_bytes)
+            .records()
+            .map(|record| {
+                let record = record.map_err(|err| ScraperError::ExcelParsing(err.to_string()))?;
+                Ok(record
+                    .iter()
+                    .map(|value| DataType::String(value.to_string()))
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+/// Downloads and parses the spreadsheet described by a config
+pub struct SpreadsheetSource {
+    config: SpreadsheetSourceConfig,
+    notices: WARNNotices,
+}
+
+impl SpreadsheetSource {
+    /// Downloads the spreadsheet, and any archived ones, which together hold
+    /// every notice the source has
+    pub fn new(
+        config: SpreadsheetSourceConfig,
+        scraper_config: ScraperConfig,
+    ) -> ScraperResult<SpreadsheetSource> {
+        let _span = info_span!("spreadsheet_source", id = %config.id).entered();
+        let workbooks = std::iter::once(&config.url)
+            .chain(config.archive_urls.iter())
+            .enumerate()
+            .map(|(idx, url)| WorkbookDownload {
+                url: url.clone(),
+                file_name: Self::create_download_filename(&config, idx),
+            })
+            .collect::<Vec<WorkbookDownload>>();
+        info!(workbook_count = workbooks.len(), "Downloading spreadsheets");
+
+        let client = scraper_config.blocking_http_client()?;
+        let download_directory = scraper_config.download_directory.join(&config.id);
+        let parser = SpreadsheetParser::new(&config);
+        let notices = BlockingWorkbookPipeline::new(&scraper_config, &client).download_and_parse(
+            workbooks,
+            &download_directory,
+            |spreadsheet_path| parser.parse_file(&spreadsheet_path),
+        )?;
+
+        info!(notice_count = notices.notices.len(), "Parsed spreadsheets");
+
+        Ok(SpreadsheetSource { config, notices })
+    }
+
+    fn create_download_filename(config: &SpreadsheetSourceConfig, idx: usize) -> String {
+        let extension = match config.format() {
+            SpreadsheetFormat::Xlsx => "xlsx",
+            SpreadsheetFormat::Csv => "csv",
+        };
+        format!("{}_warn_notices_{}.{}", config.id, idx, extension)
+    }
+}
+
+impl WarnSource for SpreadsheetSource {
+    fn id(&self) -> &str {
+        &self.config.id
+    }
+
+    fn state(&self) -> &str {
+        &self.config.state
+    }
+
+    fn fetch_notices(&self) -> ScraperResult<WARNNotices> {
+        Ok(self.notices.clone())
+    }
+
+    /// Notices received within the past week. Notices whose received date
+    /// could not be understood are left out.
+    fn fetch_weekly_notices(&self) -> ScraperResult<WARNNotices> {
+        let week_start = Local::now().date_naive() - Duration::days(DAYS_IN_WEEK);
+        let mut weekly_notices = WARNNotices::new();
+        weekly_notices.notices = self
+            .notices
+            .notices
+            .iter()
+            .filter(|notice| {
+                NaiveDate::parse_from_str(notice.date_received(), NOTICE_DATE_FORMAT)
+                    .is_ok_and(|date| date >= week_start)
+            })
+            .cloned()
+            .collect();
+        Ok(weekly_notices)
+    }
+}
@@ -0,0 +1,181 @@
+//! Abstraction over the websites WARN notices are scraped from. Each state
+//! publishes its notices differently, so each gets its own `WarnSource`
+//! implementation. Sources are looked up by id through a `SourceRegistry`.
+use std::{collections::BTreeMap, sync::Arc};
+
+use proto_generator::{
+    notice_classifier::NoticeClassifier, notice_collector::NoticeCollector, notices::WARNNotices,
+};
+use tracing::info_span;
+
+use crate::{
+    error::{ScraperError, ScraperResult},
+    layout_report::LayoutReport,
+    new_york_source::{NewYorkSource, NEW_YORK_SOURCE_ID},
+    scraper::{ScraperAdapter, MASSACHUSETTS_SOURCE_ID},
+    scraper_config::ScraperConfig,
+    spreadsheet_source::{SpreadsheetSource, SpreadsheetSourceConfig},
+};
+
+/// A website that publishes WARN notices
+pub trait WarnSource {
+    /// Short id the source is selected by, i.e. "ma"
+    fn id(&self) -> &str;
+
+    /// Two letter code of the state the source publishes notices for
+    fn state(&self) -> &str;
+
+    /// # Return
+    /// Every notice the source publishes for the current fiscal year
+    fn fetch_notices(&self) -> ScraperResult<WARNNotices>;
+
+    /// # Return
+    /// Only the notices submitted this past week
+    fn fetch_weekly_notices(&self) -> ScraperResult<WARNNotices>;
+
+    /// # Return
+    /// Which of the anchors its parser relies on were found on the fetched
+    /// page, or None if the source has no layout checks
+    fn diagnose_layout(&self) -> Option<LayoutReport> {
+        None
+    }
+
+    /// Sets the state of every notice to this source's state
+    fn tag_with_state(&self, notices: &mut WARNNotices) {
+        notices
+            .notices
+            .iter_mut()
+            .for_each(|notice| notice.set_state(self.state().to_string()));
+    }
+}
+
+/// Creates a source. Sources fetch their pages when created, so they are only
+/// created once selected.
+/// # Params
+/// * config - how the source should fetch its pages
+pub type SourceFactory =
+    Arc<dyn Fn(&ScraperConfig) -> ScraperResult<Box<dyn WarnSource>> + Send + Sync>;
+
+/// A source known to the registry
+#[derive(Clone)]
+pub struct RegisteredSource {
+    /// Human readable name of the source, i.e. "Massachusetts"
+    pub name: String,
+    pub factory: SourceFactory,
+}
+
+/// Every source that can be selected, by id
+#[derive(Clone)]
+pub struct SourceRegistry {
+    sources: BTreeMap<String, RegisteredSource>,
+}
+
+impl Default for SourceRegistry {
+    /// A registry with every source implemented by this crate
+    fn default() -> Self {
+        let mut registry = SourceRegistry::empty();
+        registry.register(MASSACHUSETTS_SOURCE_ID, "Massachusetts", |config| {
+            Ok(Box::new(ScraperAdapter::massachusetts(config.clone())?))
+        });
+        registry.register(NEW_YORK_SOURCE_ID, "New York", |config| {
+            Ok(Box::new(NewYorkSource::new(config.clone())?))
+        });
+        registry
+    }
+}
+
+impl SourceRegistry {
+    pub fn empty() -> SourceRegistry {
+        SourceRegistry {
+            sources: BTreeMap::new(),
+        }
+    }
+
+    /// Adds a source, replacing any source already registered with the id
+    pub fn register<F>(&mut self, id: &str, name: &str, factory: F)
+    where
+        F: Fn(&ScraperConfig) -> ScraperResult<Box<dyn WarnSource>> + Send + Sync + 'static,
+    {
+        self.sources.insert(
+            id.to_lowercase(),
+            RegisteredSource {
+                name: name.to_string(),
+                factory: Arc::new(factory),
+            },
+        );
+    }
+
+    /// Adds a source described by a spreadsheet source config, replacing any
+    /// source already registered with the config's id
+    pub fn register_spreadsheet(&mut self, config: SpreadsheetSourceConfig) {
+        let (id, name) = (config.id.clone(), config.name.clone());
+        self.register(&id, &name, move |scraper_config| {
+            Ok(Box::new(SpreadsheetSource::new(
+                config.clone(),
+                scraper_config.clone(),
+            )?))
+        });
+    }
+
+    /// # Return
+    /// The ids and names of every registered source, ordered by id
+    pub fn sources(&self) -> Vec<(String, String)> {
+        self.sources
+            .iter()
+            .map(|(id, source)| (id.clone(), source.name.clone()))
+            .collect()
+    }
+
+    /// Creates the source registered under the id
+    pub fn create(&self, id: &str, config: &ScraperConfig) -> ScraperResult<Box<dyn WarnSource>> {
+        let source = self
+            .sources
+            .get(id.to_lowercase().as_str())
+            .ok_or_else(|| {
+                ScraperError::UnknownSource(format!(
+                    "{}. Known sources are: {}",
+                    id,
+                    self.sources
+                        .keys()
+                        .map(String::as_str)
+                        .collect::<Vec<&str>>()
+                        .join(", ")
+                ))
+            })?;
+        (source.factory)(config)
+    }
+
+    /// Creates each of the sources, in order
+    pub fn create_all(
+        &self,
+        ids: &[String],
+        config: &ScraperConfig,
+    ) -> ScraperResult<Vec<Box<dyn WarnSource>>> {
+        ids.iter().map(|id| self.create(id, config)).collect()
+    }
+
+    /// Fetches every notice from each of the sources, merges them and
+    /// classifies those the sources left unclassified
+    pub fn fetch_notices(
+        sources: &[Box<dyn WarnSource>],
+        weekly_only: bool,
+    ) -> ScraperResult<WARNNotices> {
+        sources
+            .iter()
+            .map(|source| {
+                let _span =
+                    info_span!("fetch_notices", source = source.id(), weekly_only).entered();
+                match weekly_only {
+                    true => source.fetch_weekly_notices(),
+                    false => source.fetch_notices(),
+                }
+            })
+            .try_fold(WARNNotices::new(), |merged, notices| {
+                Ok(NoticeCollector::reduce_notices(merged, notices?))
+            })
+            .map(|mut notices| {
+                NoticeClassifier::annotate_notices(&mut notices);
+                notices
+            })
+    }
+}
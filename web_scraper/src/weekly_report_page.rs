@@ -0,0 +1,403 @@
+//! Parsing of the Massachusetts weekly report page, shared by the blocking
+//! `ScraperAdapter` and the `AsyncScraperAdapter`
+use proto_generator::notices::WARNNotices;
+use scraper::{Element, ElementRef, Html, Selector};
+
+use crate::{
+    error::{ScraperError, ScraperResult},
+    layout_report::{AnchorCheck, LayoutReport},
+    massachusetts_layout::MassachusettsLayout,
+    notice_paragraph_parser::NoticeParagraphParser,
+    scraper::{ScraperAdapter, MASSACHUSETTS_SOURCE_ID},
+    scraper_adapter,
+    scraper_adapter::ScraperSiblingElement,
+    workbook_pipeline::WorkbookDownload,
+};
+
+const DOWNLOADED_FILE_PATH_AFTER_DIRECTORY: &str = "WARN_report_20";
+const DOWNLOADED_FILE_PATH_SUFFIX: &str = ".xlsx";
+const ARCHIVE_FILE_PATH_PREFIX: &str = "WARN_report_archive_";
+const LAYOUT_HINT: &str = "run the doctor command to see what changed on the page";
+
+/// Link to the spreadsheet of this year's notices
+pub(crate) struct YearlyReport {
+    url: String,
+    /// Last two digits of the year
+    year: String,
+}
+
+impl YearlyReport {
+    /// # Return
+    /// The workbooks to download, this year's followed by those of the
+    /// archived years
+    pub(crate) fn workbooks(self, archive_workbook_urls: &[String]) -> Vec<WorkbookDownload> {
+        std::iter::once(WorkbookDownload {
+            url: self.url,
+            file_name: Self::create_download_filename(&self.year),
+        })
+        .chain(
+            archive_workbook_urls
+                .iter()
+                .enumerate()
+                .map(|(idx, url)| WorkbookDownload {
+                    url: url.clone(),
+                    file_name: format!(
+                        "{}{}{}",
+                        ARCHIVE_FILE_PATH_PREFIX, idx, DOWNLOADED_FILE_PATH_SUFFIX
+                    ),
+                }),
+        )
+        .collect()
+    }
+
+    fn create_download_filename(year: &str) -> String {
+        format!(
+            "{}{}{}",
+            DOWNLOADED_FILE_PATH_AFTER_DIRECTORY, year, DOWNLOADED_FILE_PATH_SUFFIX
+        )
+    }
+}
+
+/// What is found on the weekly report page
+pub(crate) struct WeeklyReportPage {
+    pub(crate) weekly_notices: WARNNotices,
+    pub(crate) yearly_report: Option<YearlyReport>,
+}
+
+impl WeeklyReportPage {
+    /// # Brief
+    /// Looks for each anchor the parser relies on in a weekly report page,
+    /// carrying on past missing ones wherever possible.
+    /// # Params
+    /// * html - The weekly report page
+    /// * url - Where the page was fetched from, for the report
+    /// * layout - The anchors to look for. An invalid heading selector finds
+    ///   no headings.
+    pub(crate) fn check_layout(
+        html: &str,
+        url: &str,
+        layout: &MassachusettsLayout,
+    ) -> LayoutReport {
+        let document = Html::parse_document(html);
+        let heading_selector = layout.heading_selector().ok();
+        let headings = heading_selector
+            .iter()
+            .flat_map(|selector| document.select(selector))
+            .collect::<Vec<ElementRef>>();
+        let warn_headings = headings
+            .iter()
+            .filter(|heading| {
+                scraper_adapter::element_text_to_string(heading).contains(&layout.warn_heading)
+            })
+            .copied()
+            .collect::<Vec<ElementRef>>();
+
+        let mut heading_check = AnchorCheck::new(
+            "WARN heading",
+            format!(
+                "one `{}` containing \"{}\"",
+                layout.heading_selector, layout.warn_heading
+            ),
+        );
+        let mut section_check = AnchorCheck::new(
+            "notice section",
+            "an element right after the heading, wrapping the notices two elements deep",
+        );
+        let mut paragraphs_check = AnchorCheck::new(
+            "notice paragraphs",
+            format!(
+                "at least one element of the notice section containing \"{}\"",
+                layout.labels.company
+            ),
+        );
+        let mut yearly_report_check = AnchorCheck::new(
+            "year to date link",
+            format!(
+                "an element of the notice section whose first child is a link containing \"{}\"",
+                layout.yearly_report_text
+            ),
+        );
+
+        heading_check = match warn_headings.is_empty() {
+            true => heading_check.missing(&headings),
+            false => heading_check.found(&warn_headings, true),
+        };
+
+        let notices_first_child = match warn_headings.as_slice() {
+            [heading] => match heading.next_sibling_element() {
+                None => {
+                    section_check = section_check.missing(&[*heading]);
+                    None
+                }
+                Some(section) => {
+                    let first_child = section
+                        .first_element_child()
+                        .and_then(|wrapper| wrapper.first_element_child());
+                    section_check = match first_child {
+                        Some(_) => section_check.found(&[section], true),
+                        None => section_check.missing(&[section]),
+                    };
+                    first_child
+                }
+            },
+            _ => None,
+        };
+
+        if let Some(first_child) = notices_first_child {
+            let siblings = ScraperSiblingElement::new(first_child).collect::<Vec<ElementRef>>();
+
+            let parser = NoticeParagraphParser::new(&layout.labels);
+            let paragraphs = siblings
+                .iter()
+                .filter(|sibling| parser.is_notice(**sibling))
+                .copied()
+                .collect::<Vec<ElementRef>>();
+            paragraphs_check = match paragraphs.is_empty() {
+                true => paragraphs_check.missing(&siblings),
+                false => paragraphs_check.found(&paragraphs, false),
+            };
+
+            let yearly_reports = siblings
+                .iter()
+                .filter_map(|sibling| {
+                    Self::is_yearly_report_element(sibling.first_element_child(), layout)
+                })
+                .collect::<Vec<ElementRef>>();
+            yearly_report_check = match yearly_reports.first() {
+                None => yearly_report_check.missing(&siblings),
+                Some(link) if link.value().attr("href").is_none() => {
+                    yearly_report_check.missing(&[*link])
+                }
+                Some(link) => yearly_report_check.found(&[*link], true),
+            };
+        }
+
+        LayoutReport {
+            source: MASSACHUSETTS_SOURCE_ID.to_string(),
+            url: url.to_string(),
+            checks: vec![
+                heading_check,
+                section_check,
+                paragraphs_check,
+                yearly_report_check,
+            ],
+        }
+    }
+
+    /// Parses the notices and, if wanted, the link to the yearly report out
+    /// of the page
+    /// # Params
+    /// * html - The weekly report page
+    /// * base_url - What the link to the yearly report is relative to
+    /// * layout - Where things are on the page
+    /// * find_yearly_report - Whether to look for the link to the yearly report
+    pub(crate) fn parse(
+        html: &str,
+        base_url: &str,
+        layout: &MassachusettsLayout,
+        find_yearly_report: bool,
+    ) -> ScraperResult<WeeklyReportPage> {
+        let document = Html::parse_document(html);
+        let notice_section: ElementRef = Self::get_submit_notice_reference(&document, layout)?;
+        let notice_section_first_sibling = Self::get_notices_first_child(notice_section)?;
+
+        let weekly_notices = Self::get_notices_from_section(notice_section_first_sibling, layout)?;
+        let yearly_report = match find_yearly_report {
+            true => Some(Self::find_yearly_report(
+                notice_section_first_sibling,
+                base_url,
+                layout,
+            )?),
+            false => None,
+        };
+
+        Ok(WeeklyReportPage {
+            weekly_notices,
+            yearly_report,
+        })
+    }
+
+    /// Returns an element reference to the first element under the submission
+    /// header
+    fn get_submit_notice_reference<'a>(
+        main_page: &'a Html,
+        layout: &MassachusettsLayout,
+    ) -> ScraperResult<ElementRef<'a>> {
+        let selector: Selector = layout.heading_selector()?;
+
+        let warning_headers = main_page
+            .select(&selector)
+            .filter_map(|child: ElementRef<'a>| {
+                ElementRef::wrap(*child).and_then(|el| {
+                    match scraper_adapter::element_text_to_string(&el)
+                        .contains(&layout.warn_heading)
+                    {
+                        true => Some(el),
+                        false => None,
+                    }
+                })
+            })
+            .collect::<Vec<ElementRef<'a>>>();
+
+        let warning_header_el: ElementRef<'a> = match warning_headers.as_slice() {
+            [warning_header_el] => *warning_header_el,
+            [] => {
+                return Err(ScraperError::Parsing(format!(
+                    "Found no heading that matches {}; {}",
+                    layout.warn_heading, LAYOUT_HINT
+                )))
+            }
+            _ => {
+                return Err(ScraperError::Parsing(format!(
+                    "Found multiple headings that match {}; {}",
+                    layout.warn_heading, LAYOUT_HINT
+                )))
+            }
+        };
+
+        let notices_section: ElementRef<'a> =
+            warning_header_el.next_sibling_element().ok_or_else(|| {
+                ScraperError::Parsing(format!(
+                    "No sibling element to warning notice heading; {}",
+                    LAYOUT_HINT
+                ))
+            })?;
+
+        Ok(notices_section)
+    }
+
+    /// # Return
+    /// A representation of the siblings within the notice section
+    fn get_notices_first_child(
+        notice_section_parent: ElementRef,
+    ) -> ScraperResult<ScraperSiblingElement> {
+        let inner_notices_first_child = Self::unwrap_inner_notices(notice_section_parent)?;
+        let sibling_manager: ScraperSiblingElement =
+            ScraperSiblingElement::new(inner_notices_first_child);
+        Ok(sibling_manager)
+    }
+
+    fn get_notices_from_section<'a>(
+        notice_section_first_sibling: ScraperSiblingElement<'a>,
+        layout: &MassachusettsLayout,
+    ) -> ScraperResult<WARNNotices> {
+        let mut notices = WARNNotices::new();
+        notices.notices =
+            NoticeParagraphParser::new(&layout.labels).parse_elements(notice_section_first_sibling);
+
+        Ok(notices)
+    }
+
+    ///
+    /// # Return
+    /// The url of the spreadsheet of the year to date warn notices, and the
+    /// last two digits of its year
+    ///
+    /// # Params
+    /// * The reference to the first element of the notice paragraphs
+    fn find_yearly_report<'a>(
+        notice_section_first_sibling: ScraperSiblingElement<'a>,
+        base_url: &str,
+        layout: &MassachusettsLayout,
+    ) -> ScraperResult<YearlyReport> {
+        let notice_children =
+            notice_section_first_sibling.map(|notice_element| notice_element.first_element_child());
+        let ytd_notice_element: ElementRef<'a> =
+            Self::flatten_yearly_report(notice_children, layout)?;
+
+        let href = ytd_notice_element.value().attr("href").ok_or_else(|| {
+            ScraperError::Parsing(format!(
+                "Yearly report element {} does not have an href!",
+                scraper_adapter::element_text_to_string(&ytd_notice_element)
+            ))
+        })?;
+
+        Ok(YearlyReport {
+            url: ScraperAdapter::construct_full_url(base_url, href)?,
+            year: Self::get_year_from_yearly_report_element(ytd_notice_element),
+        })
+    }
+
+    /// There is an empty section between the the parent section of notices
+    /// (the sibling to the heading), and the start of the notices. Strip that away.
+    /// # Return
+    /// The 1st child element of the notices section!
+    fn unwrap_inner_notices<'a>(
+        notice_section_parent: ElementRef<'a>,
+    ) -> ScraperResult<ElementRef<'a>> {
+        notice_section_parent
+            .first_element_child()
+            .and_then(|wrapper| wrapper.first_element_child())
+            .ok_or_else(|| {
+                ScraperError::Parsing(format!(
+                    "Notice section is not wrapped two elements deep; {}",
+                    LAYOUT_HINT
+                ))
+            })
+    }
+
+    /// # Brief
+    /// Determines if the child of a notice element has the text wrapper
+    /// expected for the yearly report url
+    ///
+    /// # Return
+    /// * The nested element with the href if it exists
+    /// * None if no element fits the criteria
+    fn is_yearly_report_element<'a>(
+        notice_element_child: Option<ElementRef<'a>>,
+        layout: &MassachusettsLayout,
+    ) -> Option<ElementRef<'a>> {
+        match notice_element_child {
+            None => None,
+            Some(notice_child) => {
+                let child_text = scraper_adapter::element_text_to_string(&notice_child);
+                if child_text.contains(&layout.yearly_report_text) {
+                    Some(notice_child)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// # Brief
+    /// Taking an iterator to (potential) children of notice elements, find
+    /// the one most recently element containing an href to a spreadsheet of
+    /// WARN reports.
+    /// # Returns
+    /// * Error - if the href element can not be found
+    /// * The element if it exists
+    fn flatten_yearly_report<'a, I>(
+        notice_children_iter: I,
+        layout: &MassachusettsLayout,
+    ) -> ScraperResult<ElementRef<'a>>
+    where
+        I: Iterator<Item = Option<ElementRef<'a>>>,
+    {
+        let yearly_report_element = notice_children_iter
+            .filter_map(|notice_child| Self::is_yearly_report_element(notice_child, layout))
+            .collect::<Vec<_>>()
+            .first()
+            .ok_or_else(|| {
+                ScraperError::Parsing(format!(
+                    "No elements contain the prefix {} expected for yearly report; {}",
+                    layout.yearly_report_text, LAYOUT_HINT
+                ))
+            })?
+            .to_owned();
+
+        Ok(yearly_report_element)
+    }
+
+    /// Retrieve the year from the full user-available string. The user is given
+    /// the year....We just want to extract it from there
+    fn get_year_from_yearly_report_element<'a>(yearly_report_element: ElementRef<'a>) -> String {
+        let full_text = scraper_adapter::element_text_to_string(&yearly_report_element);
+        let year_digits_reversed = full_text.chars().rev().take(2).collect::<String>();
+        year_digits_reversed
+            .chars()
+            .rev()
+            .take(2)
+            .collect::<String>()
+    }
+}
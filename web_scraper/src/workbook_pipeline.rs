@@ -0,0 +1,203 @@
+//! Downloads and parses several workbooks at once, with at most a configured
+//! number of workbooks in flight. Results are merged in the order the
+//! workbooks were given, however the downloads finish.
+//!
+//! `BlockingWorkbookPipeline` downloads and parses each workbook on a thread
+//! of its own. With the `async` feature, `WorkbookPipeline` downloads
+//! concurrently on the runtime and parses on tokio's blocking thread pool.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "async")]
+use futures::{stream, StreamExt, TryStreamExt};
+use proto_generator::{notice_collector::NoticeCollector, notices::WARNNotices};
+use tracing::{debug, info, instrument};
+
+use crate::{
+    bounded_threads::BoundedThreads, download_manager::DownloaderWrapper, error::ScraperResult,
+    http_client::BlockingHttpClient, scraper_config::ScraperConfig,
+};
+#[cfg(feature = "async")]
+use crate::{error::ScraperError, http_client::HttpClient};
+
+/// A workbook to download and parse
+#[derive(Clone, Debug)]
+pub(crate) struct WorkbookDownload {
+    pub(crate) url: String,
+    /// Name the workbook is saved under while it is parsed
+    pub(crate) file_name: String,
+}
+
+impl WorkbookDownload {
+    /// Downloaders empty and delete their directory, so each workbook gets
+    /// one of its own
+    fn directory(download_directory: &Path, idx: usize) -> PathBuf {
+        download_directory.join(idx.to_string())
+    }
+
+    fn log_parsed(&self, notices: &WARNNotices) {
+        info!(
+            url = %self.url,
+            notice_count = notices.notices.len(),
+            "Parsed workbook"
+        );
+    }
+
+    /// Merges the notices of each workbook, in the order given
+    fn merge(parsed_workbooks: Vec<WARNNotices>) -> WARNNotices {
+        parsed_workbooks
+            .into_iter()
+            .fold(WARNNotices::new(), NoticeCollector::reduce_notices)
+    }
+}
+
+pub(crate) struct BlockingWorkbookPipeline<'a> {
+    config: &'a ScraperConfig,
+    client: &'a BlockingHttpClient,
+}
+
+impl<'a> BlockingWorkbookPipeline<'a> {
+    pub(crate) fn new(config: &'a ScraperConfig, client: &'a BlockingHttpClient) -> Self {
+        BlockingWorkbookPipeline { config, client }
+    }
+
+    /// # Params
+    /// * workbooks - Workbooks to download, in the order their notices are merged
+    /// * download_directory - Each workbook is downloaded into a subdirectory
+    ///   of this, which is deleted once it is parsed
+    /// * parse - Parses a downloaded workbook. Called on the workbook's thread.
+    /// # Return
+    /// The notices of every workbook, or the error of the first workbook
+    /// that failed
+    #[instrument(skip_all, fields(workbook_count = workbooks.len()))]
+    pub(crate) fn download_and_parse<P>(
+        &self,
+        workbooks: Vec<WorkbookDownload>,
+        download_directory: &Path,
+        parse: P,
+    ) -> ScraperResult<WARNNotices>
+    where
+        P: Fn(PathBuf) -> ScraperResult<WARNNotices> + Sync,
+    {
+        let parsed_workbooks = BoundedThreads::map_ordered(
+            &workbooks,
+            self.config.max_concurrent_downloads,
+            |idx, workbook| {
+                self.download_and_parse_one(
+                    workbook,
+                    WorkbookDownload::directory(download_directory, idx),
+                    &parse,
+                )
+            },
+        )
+        .into_iter()
+        .collect::<ScraperResult<Vec<WARNNotices>>>()?;
+
+        // Only succeeds once every workbook directory is gone
+        let _ = fs::remove_dir(download_directory);
+
+        Ok(WorkbookDownload::merge(parsed_workbooks))
+    }
+
+    fn download_and_parse_one<P>(
+        &self,
+        workbook: &WorkbookDownload,
+        workbook_directory: PathBuf,
+        parse: &P,
+    ) -> ScraperResult<WARNNotices>
+    where
+        P: Fn(PathBuf) -> ScraperResult<WARNNotices>,
+    {
+        let downloaded_file_path = workbook_directory.join(&workbook.file_name);
+        let downloader = DownloaderWrapper::new(
+            workbook.url.clone(),
+            &downloaded_file_path,
+            &workbook_directory,
+        );
+        downloader.download_file(self.client)?;
+
+        debug!(url = %workbook.url, "Parsing workbook");
+        let notices = parse(downloaded_file_path.clone())?;
+        workbook.log_parsed(&notices);
+
+        Ok(notices)
+    }
+}
+
+#[cfg(feature = "async")]
+pub(crate) struct WorkbookPipeline<'a> {
+    config: &'a ScraperConfig,
+    client: &'a HttpClient,
+}
+
+#[cfg(feature = "async")]
+impl<'a> WorkbookPipeline<'a> {
+    pub(crate) fn new(config: &'a ScraperConfig, client: &'a HttpClient) -> Self {
+        WorkbookPipeline { config, client }
+    }
+
+    /// Async counterpart of `BlockingWorkbookPipeline::download_and_parse`.
+    /// `parse` is called on the blocking thread pool.
+    #[instrument(skip_all, fields(workbook_count = workbooks.len()))]
+    pub(crate) async fn download_and_parse<P>(
+        &self,
+        workbooks: Vec<WorkbookDownload>,
+        download_directory: &Path,
+        parse: P,
+    ) -> ScraperResult<WARNNotices>
+    where
+        P: Fn(PathBuf) -> ScraperResult<WARNNotices> + Clone + Send + 'static,
+    {
+        let parsed_workbooks = stream::iter(workbooks.into_iter().enumerate())
+            .map(|(idx, workbook)| {
+                self.download_and_parse_one(
+                    workbook,
+                    WorkbookDownload::directory(download_directory, idx),
+                    parse.clone(),
+                )
+            })
+            // buffered, unlike buffer_unordered, yields in the order given
+            .buffered(self.config.max_concurrent_downloads.max(1))
+            .try_collect::<Vec<WARNNotices>>()
+            .await?;
+
+        // Only succeeds once every workbook directory is gone
+        let _ = tokio::fs::remove_dir(download_directory).await;
+
+        Ok(WorkbookDownload::merge(parsed_workbooks))
+    }
+
+    async fn download_and_parse_one<P>(
+        &self,
+        workbook: WorkbookDownload,
+        workbook_directory: PathBuf,
+        parse: P,
+    ) -> ScraperResult<WARNNotices>
+    where
+        P: Fn(PathBuf) -> ScraperResult<WARNNotices> + Send + 'static,
+    {
+        let downloaded_file_path = workbook_directory.join(&workbook.file_name);
+        let downloader = DownloaderWrapper::new(
+            workbook.url.clone(),
+            &downloaded_file_path,
+            &workbook_directory,
+        );
+        downloader.download_file_async(self.client).await?;
+
+        debug!(url = %workbook.url, "Parsing workbook");
+        let workbook_path = downloaded_file_path.clone();
+        let notices = tokio::task::spawn_blocking(move || parse(workbook_path))
+            .await
+            .map_err(|err| {
+                ScraperError::ExcelParsing(format!(
+                    "Parsing the workbook from {} failed: {}",
+                    workbook.url, err
+                ))
+            })??;
+        workbook.log_parsed(&notices);
+
+        Ok(notices)
+    }
+}
@@ -7,6 +7,8 @@ use proto_generator::{
 };
 use std::path::PathBuf;
 
+use tracing::{debug, instrument, warn};
+
 use crate::error::{ScraperError, ScraperResult};
 
 /// the headings start at row 3, but the data we need starts in row 4
@@ -14,37 +16,30 @@ static START_ROW_NUMBER: u32 = 3;
 
 pub(crate) struct YearToDateParser {
     workbook: Excel,
-    is_verbose: bool,
 }
 
 impl YearToDateParser {
-    pub fn new(path_to_file: &PathBuf, is_verbose: bool) -> ScraperResult<YearToDateParser> {
-        if is_verbose {
-            println!("Opening {}", path_to_file.display());
-        }
+    pub fn new(path_to_file: &PathBuf) -> ScraperResult<YearToDateParser> {
+        debug!(path = %path_to_file.display(), "Opening workbook");
 
         let workbook = match Excel::open(path_to_file) {
-            Err(office_error) => panic!("Error opening workbook: {}", office_error.to_string()),
+            Err(office_error) => panic!("Error opening workbook: {}", office_error),
             Ok(workbook) => workbook,
         };
 
-        Ok(YearToDateParser {
-            workbook,
-            is_verbose,
-        })
+        Ok(YearToDateParser { workbook })
     }
 
+    #[instrument(skip(self))]
     pub fn parse_for_notices(&mut self) -> ScraperResult<WARNNotices> {
         let sheet_names: Vec<String> = match self.workbook.sheet_names() {
-            Err(office_error) => panic!("Error getting sheet names: {}", office_error.to_string()),
+            Err(office_error) => panic!("Error getting sheet names: {}", office_error),
             Ok(sheet_names) => sheet_names,
         };
 
         sheet_names
             .into_iter()
-            .map(|sheet_name| {
-                Self::parse_worksheet(sheet_name, &mut self.workbook, self.is_verbose)
-            })
+            .map(|sheet_name| Self::parse_worksheet(sheet_name, &mut self.workbook))
             .collect::<ScraperResult<Vec<WARNNotices>>>()?
             .into_iter()
             .reduce(|a: WARNNotices, b: WARNNotices| NoticeCollector::reduce_notices(a, b))
@@ -56,13 +51,10 @@ impl YearToDateParser {
     }
 
     /// Parses an individual worksheet for it's notices
-    fn parse_worksheet(
-        sheet_name: String,
-        workbook: &mut Excel,
-        is_verbose: bool,
-    ) -> ScraperResult<WARNNotices> {
+    #[instrument(skip(workbook))]
+    fn parse_worksheet(sheet_name: String, workbook: &mut Excel) -> ScraperResult<WARNNotices> {
         let sheet_range = match workbook.worksheet_range(sheet_name.as_str()) {
-            Err(office_error) => panic!("Error getting sheet range: {}", office_error.to_string()),
+            Err(office_error) => panic!("Error getting sheet range: {}", office_error),
             Ok(sheet_range) => sheet_range,
         };
 
@@ -75,7 +67,7 @@ impl YearToDateParser {
         let num_rows = sheet_range.rows().count() as u32;
 
         let num_rows_with_data = num_rows
-            .checked_sub(START_ROW_NUMBER.into())
+            .checked_sub(START_ROW_NUMBER)
             .ok_or_else(subtract_err)? as u32;
 
         let mut worksheet_notices: WARNNotices = WARNNotices::new();
@@ -89,12 +81,12 @@ impl YearToDateParser {
 
             // convert to a notice
             let notice = WARNNotice {
-                firm_name: Self::check_for_value(firm_name, is_verbose),
-                firm_locations: Self::check_for_value(firm_locations, is_verbose),
-                affected_employees: Self::check_for_value(affected_employees, is_verbose),
+                firm_name: Self::check_for_value(firm_name),
+                firm_locations: Self::check_for_value(firm_locations),
+                affected_employees: Self::check_for_value(affected_employees),
                 effective_date: Self::convert_date(effective_date),
                 date_received: Self::convert_date(date_received),
-                special_fields: ::std::default::Default::default(),
+                ..::std::default::Default::default()
             };
 
             if !Self::are_no_fields_present(&notice) {
@@ -102,6 +94,10 @@ impl YearToDateParser {
             }
         }
 
+        debug!(
+            notice_count = worksheet_notices.notices.len(),
+            "Parsed worksheet"
+        );
         Ok(worksheet_notices)
     }
 
@@ -109,14 +105,12 @@ impl YearToDateParser {
     /// # Return
     /// * None if the cell is not a string or cannot be converted to a string
     /// * Some(item) if it is string-compatible
-    fn check_for_value(data: &DataType, is_verbose: bool) -> Option<String> {
+    fn check_for_value(data: &DataType) -> Option<String> {
         match data {
             DataType::Bool(_) => None,
             DataType::Empty => None,
             DataType::Error(err) => {
-                if is_verbose {
-                    println!("Error from cell! {:?}", err);
-                }
+                warn!(error = ?err, "Error from cell");
                 None
             }
             DataType::Int(value) => Some(value.to_string()),
@@ -160,7 +154,7 @@ impl YearToDateParser {
     /// Context for and implementation of idea below come from this rust-excel
     /// thread:
     /// https://github.com/tafia/calamine/issues/116#issuecomment-414025552
-    fn from_days_since_1900<T>(raw_days_since_1900: T) -> NaiveDate
+    pub(crate) fn from_days_since_1900<T>(raw_days_since_1900: T) -> NaiveDate
     where
         T: std::ops::Sub<i64, Output = i64>,
     {
@@ -0,0 +1,165 @@
+use proto_generator::{
+    company_resolver::CompanyResolver,
+    notice_collector::NoticeCollector,
+    notice_statistics::NoticeStatistics,
+    notices::{WARNNotice, WARNNotices},
+};
+use web_scraper::{company_aliases::CompanyAliases, error::ScraperError};
+
+const ALIASES: &str = r#"
+[[company]]
+name = "Acme Labs"
+aliases = ["Acme Laboratories of New England"]
+"#;
+
+fn notices(firm_names: &[&str]) -> WARNNotices {
+    let mut notices = WARNNotices::new();
+    notices.notices = firm_names
+        .iter()
+        .map(|firm_name| {
+            let mut notice = WARNNotice::new();
+            notice.set_firm_name(firm_name.to_string());
+            notice.set_affected_employees("10".to_string());
+            notice
+        })
+        .collect();
+    notices
+}
+
+fn company_names(notices: &WARNNotices) -> Vec<&str> {
+    notices
+        .notices
+        .iter()
+        .map(|notice| notice.company_name())
+        .collect()
+}
+
+#[test]
+fn normalises_variants_of_a_name_alike() {
+    for firm_name in [
+        "Acme Inc",
+        "ACME, Inc.",
+        "Acme Incorporated d/b/a Acme Labs",
+        "The Acme Co.",
+        "Acme L.L.C.",
+        "Acme, Inc. (d.b.a. Acme Labs)",
+    ] {
+        assert_eq!(
+            CompanyResolver::normalise(firm_name),
+            "acme",
+            "{}",
+            firm_name
+        );
+    }
+    assert_eq!(
+        CompanyResolver::normalise("Smith & Sons Corp"),
+        CompanyResolver::normalise("Smith and Sons Corporation")
+    );
+    assert_ne!(
+        CompanyResolver::normalise("Acme Labs"),
+        CompanyResolver::normalise("Acme")
+    );
+}
+
+#[test]
+fn names_a_company_by_its_most_used_spelling() {
+    let mut notices = notices(&["ACME, Inc.", "Acme Inc", "Acme Inc", "Bay State Bakery"]);
+
+    CompanyResolver::default().resolve(&mut notices);
+
+    assert_eq!(
+        company_names(&notices),
+        vec!["Acme Inc", "Acme Inc", "Acme Inc", "Bay State Bakery"]
+    );
+    assert_eq!(
+        notices.notices[0].company_id(),
+        notices.notices[1].company_id()
+    );
+    assert_ne!(
+        notices.notices[0].company_id(),
+        notices.notices[3].company_id()
+    );
+}
+
+#[test]
+fn company_ids_are_stable_across_spellings_and_scrapes() {
+    let mut first_scrape = notices(&["Acme Inc"]);
+    let mut second_scrape = notices(&["ACME INCORPORATED", "Bay State Bakery"]);
+
+    CompanyResolver::default().resolve(&mut first_scrape);
+    CompanyResolver::default().resolve(&mut second_scrape);
+
+    assert_eq!(
+        first_scrape.notices[0].company_id(),
+        second_scrape.notices[0].company_id()
+    );
+    assert_eq!(
+        first_scrape.notices[0].company_id(),
+        CompanyResolver::company_id("acme")
+    );
+}
+
+#[test]
+fn aliases_tie_names_to_their_canonical_company() {
+    let resolver = CompanyAliases::parse(ALIASES).unwrap();
+    let mut notices = notices(&[
+        "Acme Laboratories of New England, LLC",
+        "Acme Labs Inc.",
+        "Acme Inc",
+    ]);
+
+    resolver.resolve(&mut notices);
+
+    assert_eq!(
+        company_names(&notices),
+        vec!["Acme Labs", "Acme Labs", "Acme Inc"]
+    );
+    assert_eq!(
+        notices.notices[0].company_id(),
+        notices.notices[1].company_id()
+    );
+}
+
+#[test]
+fn search_and_stats_group_notices_by_company() {
+    let resolver = CompanyAliases::parse(ALIASES).unwrap();
+    let mut notices = notices(&[
+        "Acme Laboratories of New England",
+        "Acme Labs Inc.",
+        "Bay State Bakery",
+    ]);
+    resolver.resolve(&mut notices);
+    let acme_id = notices.notices[0].company_id().to_string();
+
+    let by_name = NoticeCollector::search_notices_for_company(notices.clone(), "acme labs");
+    let by_id = NoticeCollector::search_notices_for_company(notices.clone(), &acme_id);
+    let statistics = NoticeStatistics::compute(&notices, 10);
+
+    assert_eq!(by_name.notices.len(), 2);
+    assert_eq!(by_id.notices.len(), 2);
+    assert_eq!(statistics.top_employers[0].key(), "Acme Labs");
+    assert_eq!(statistics.top_employers[0].notice_count(), 2);
+    assert_eq!(statistics.top_employers[0].affected_employees(), 20);
+}
+
+#[test]
+fn rejects_invalid_alias_files() {
+    let invalid_files = [
+        "[[company]]\naliases = [\"Acme\"]\n",
+        "[[company]]\nname = \"Acme\"\nalias = [\"Acme Labs\"]\n",
+        "[[company]]\nname = \" . \"\n",
+        "[[company]]\nname = \"Acme Labs\"\naliases = [\"Acme\"]\n\n\
+         [[company]]\nname = \"Acme Widgets\"\naliases = [\"ACME, Inc.\"]\n",
+    ];
+
+    for aliases_text in invalid_files {
+        assert!(
+            matches!(
+                CompanyAliases::parse(aliases_text),
+                Err(ScraperError::SourceConfig(_))
+            ),
+            "{}",
+            aliases_text
+        );
+    }
+}
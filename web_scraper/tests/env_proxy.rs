@@ -0,0 +1,94 @@
+//! Sets proxy environment variables, so it runs in a process of its own
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use web_scraper::scraper_config::ScraperConfig;
+
+/// Local server answering every request with the same body
+struct StubServer {
+    url: String,
+    requests: Arc<AtomicUsize>,
+}
+
+impl StubServer {
+    fn start(body: &'static str) -> StubServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+
+        let served = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                served.fetch_add(1, Ordering::SeqCst);
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        StubServer { url, requests }
+    }
+
+    fn request_count(&self) -> usize {
+        self.requests.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn environment_proxy_skips_the_configured_no_proxy_hosts() {
+    let proxy = StubServer::start("proxied");
+    let direct = StubServer::start("direct");
+    for name in [
+        "HTTPS_PROXY",
+        "https_proxy",
+        "http_proxy",
+        "NO_PROXY",
+        "no_proxy",
+    ] {
+        std::env::remove_var(name);
+    }
+    std::env::set_var("HTTP_PROXY", &proxy.url);
+
+    let client = ScraperConfig::builder()
+        .timeout(Duration::from_secs(5))
+        .min_request_interval(Duration::ZERO)
+        .respect_robots_txt(false)
+        .no_proxy(vec!["127.0.0.1".to_string()])
+        .build()
+        .blocking_http_client()
+        .unwrap();
+
+    let direct_body = client
+        .get(&format!("{}/notices", direct.url))
+        .unwrap()
+        .text()
+        .unwrap();
+    let proxied_body = client
+        .get("http://warn.invalid/notices")
+        .unwrap()
+        .text()
+        .unwrap();
+
+    assert_eq!(direct_body, "direct");
+    assert_eq!(proxied_body, "proxied");
+    assert_eq!(direct.request_count(), 1);
+    assert_eq!(proxy.request_count(), 1);
+}
@@ -0,0 +1,363 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use web_scraper::{
+    cassette::Cassette,
+    error::ScraperError,
+    http_client::RateLimiter,
+    scraper_config::{ScraperConfig, ScraperConfigBuilder, DEFAULT_USER_AGENT},
+};
+
+/// Local server answering each request with the next of its scripted
+/// responses, then with 404s
+struct FlakyServer {
+    url: String,
+    requests: Arc<Mutex<Vec<Instant>>>,
+}
+
+impl FlakyServer {
+    fn start(responses: &[&str]) -> FlakyServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/notices", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let mut responses: Vec<String> = responses.iter().map(|r| r.to_string()).collect();
+        responses.reverse();
+
+        let served = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                    line.clear();
+                }
+                served.lock().unwrap().push(Instant::now());
+
+                let response = responses
+                    .pop()
+                    .unwrap_or_else(|| "404 Not Found".to_string());
+                let (head, body) = response.split_once("\n\n").unwrap_or((&response, ""));
+                let (status, headers) = head.split_once('\n').unwrap_or((head, ""));
+                let headers: String = headers.lines().map(|h| format!("{}\r\n", h)).collect();
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    headers,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+
+        FlakyServer { url, requests }
+    }
+
+    fn request_times(&self) -> Vec<Instant> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+fn config() -> ScraperConfigBuilder {
+    ScraperConfig::builder()
+        .timeout(Duration::from_secs(5))
+        .retry_delay(Duration::from_millis(10))
+        .max_retry_delay(Duration::from_secs(2))
+        .min_request_interval(Duration::ZERO)
+        .respect_robots_txt(false)
+}
+
+#[test]
+fn retries_server_errors_until_success() {
+    let server = FlakyServer::start(&[
+        "503 Service Unavailable",
+        "500 Internal Server Error",
+        "200 OK\n\nnotices",
+    ]);
+    let client = config().build().blocking_http_client().unwrap();
+
+    let body = client.get(&server.url).unwrap().text().unwrap();
+
+    assert_eq!(body, "notices");
+    assert_eq!(server.request_times().len(), 3);
+}
+
+#[test]
+fn gives_up_after_max_retries() {
+    let server = FlakyServer::start(&["503 Service Unavailable"; 5]);
+    let config = config().max_retries(2).build();
+    let client = config.blocking_http_client().unwrap();
+
+    assert!(client.get(&server.url).is_err());
+    assert_eq!(server.request_times().len(), 3);
+}
+
+#[test]
+fn does_not_retry_client_errors() {
+    let server = FlakyServer::start(&["404 Not Found", "200 OK\n\nnotices"]);
+    let client = config().build().blocking_http_client().unwrap();
+
+    assert!(client.get(&server.url).is_err());
+    assert_eq!(server.request_times().len(), 1);
+}
+
+#[test]
+fn waits_as_long_as_retry_after_asks() {
+    let server =
+        FlakyServer::start(&["429 Too Many Requests\nRetry-After: 1", "200 OK\n\nnotices"]);
+    let client = config().build().blocking_http_client().unwrap();
+
+    client.get(&server.url).unwrap();
+
+    let requests = server.request_times();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1] - requests[0] >= Duration::from_secs(1));
+}
+
+#[test]
+fn gives_up_when_retry_after_exceeds_max_delay() {
+    let server = FlakyServer::start(&[
+        "503 Service Unavailable\nRetry-After: 3600",
+        "200 OK\n\nnotices",
+    ]);
+    let client = config().build().blocking_http_client().unwrap();
+
+    assert!(client.get(&server.url).is_err());
+    assert_eq!(server.request_times().len(), 1);
+}
+
+#[test]
+fn spaces_requests_to_the_same_host() {
+    let server = FlakyServer::start(&["200 OK\n\na", "200 OK\n\nb", "200 OK\n\nc"]);
+    let config = config()
+        .min_request_interval(Duration::from_millis(200))
+        .build();
+    // Clients created from the same config share the schedule
+    let first_client = config.blocking_http_client().unwrap();
+    let second_client = config.blocking_http_client().unwrap();
+
+    first_client.get(&server.url).unwrap();
+    second_client.get(&server.url).unwrap();
+    first_client.get(&server.url).unwrap();
+
+    // Requests are spaced by when they are sent, so connection setup can eat
+    // into the gap seen by the server
+    let requests = server.request_times();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .windows(2)
+        .all(|pair| pair[1] - pair[0] >= Duration::from_millis(100)));
+}
+
+#[test]
+fn rate_limiter_books_a_slot_per_interval_for_each_host() {
+    let interval = Duration::from_millis(200);
+    let rate_limiter = RateLimiter::default();
+    // Clones share the schedule
+    let shared = rate_limiter.clone();
+
+    assert_eq!(
+        rate_limiter.reserve("www.mass.gov", interval),
+        Duration::ZERO
+    );
+    let second_wait = shared.reserve("www.mass.gov", interval);
+    let third_wait = rate_limiter.reserve("www.mass.gov", interval);
+    assert_eq!(rate_limiter.reserve("dol.ny.gov", interval), Duration::ZERO);
+
+    assert!(second_wait > interval / 2 && second_wait <= interval);
+    assert!(third_wait > interval + interval / 2 && third_wait <= interval * 2);
+}
+
+#[test]
+fn rate_limiter_does_not_wait_once_the_interval_has_passed() {
+    let interval = Duration::from_millis(20);
+    let rate_limiter = RateLimiter::default();
+
+    rate_limiter.reserve("www.mass.gov", interval);
+    thread::sleep(interval * 2);
+
+    assert_eq!(
+        rate_limiter.reserve("www.mass.gov", interval),
+        Duration::ZERO
+    );
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_client_retries_until_success() {
+    let server = FlakyServer::start(&["502 Bad Gateway", "200 OK\n\nnotices"]);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+
+    let body = runtime.block_on(async {
+        let client = config().build().http_client().unwrap();
+        client.get(&server.url).await.unwrap().text().await.unwrap()
+    });
+
+    assert_eq!(body, "notices");
+    assert_eq!(server.request_times().len(), 2);
+}
+
+#[test]
+fn refuses_urls_disallowed_by_robots_txt() {
+    let server = FlakyServer::start(&[
+        "200 OK\n\nUser-agent: *\nDisallow: /notices",
+        "200 OK\n\nnotices",
+    ]);
+    let client = config()
+        .respect_robots_txt(true)
+        .build()
+        .blocking_http_client()
+        .unwrap();
+
+    let result = client.get(&server.url);
+
+    assert!(matches!(result, Err(ScraperError::DisallowedByRobots(_))));
+    assert_eq!(server.request_times().len(), 1);
+}
+
+#[test]
+fn fetches_robots_txt_once_per_host() {
+    let server = FlakyServer::start(&["404 Not Found", "200 OK\n\na", "200 OK\n\nb"]);
+    let client = config()
+        .respect_robots_txt(true)
+        .build()
+        .blocking_http_client()
+        .unwrap();
+
+    assert_eq!(client.get(&server.url).unwrap().text().unwrap(), "a");
+    assert_eq!(client.get(&server.url).unwrap().text().unwrap(), "b");
+    assert_eq!(server.request_times().len(), 3);
+}
+
+#[test]
+fn sends_contact_in_user_agent() {
+    let config = config().contact("ops@example.com").build();
+
+    assert_eq!(
+        config.user_agent_header(),
+        format!("{} (+ops@example.com)", DEFAULT_USER_AGENT)
+    );
+}
+
+#[test]
+fn sends_requests_through_the_proxy() {
+    let proxy = FlakyServer::start(&["200 OK\n\nproxied"]);
+    let proxy_url = proxy.url.trim_end_matches("/notices").to_string();
+    let client = config()
+        .proxy(proxy_url)
+        .build()
+        .blocking_http_client()
+        .unwrap();
+
+    let body = client
+        .get("http://warn.invalid/notices")
+        .unwrap()
+        .text()
+        .unwrap();
+
+    assert_eq!(body, "proxied");
+    assert_eq!(proxy.request_times().len(), 1);
+}
+
+#[test]
+fn rejects_root_certificate_files_without_certificates() {
+    let not_a_certificate = std::env::temp_dir().join("warn_scraper_not_a_certificate.pem");
+    std::fs::write(&not_a_certificate, "not a certificate").unwrap();
+
+    let result = config()
+        .root_certificates(vec![not_a_certificate.clone()])
+        .build()
+        .blocking_http_client();
+    std::fs::remove_file(not_a_certificate).unwrap();
+
+    assert!(matches!(result, Err(ScraperError::NetworkConfig(_))));
+}
+
+#[test]
+fn replays_recorded_responses_without_the_network() {
+    let server = FlakyServer::start(&[
+        "503 Service Unavailable",
+        "200 OK\nContent-Type: text/csv\n\nFirm,Employees",
+    ]);
+    let cassette_directory = std::env::temp_dir().join("warn_scraper_cassette_replay");
+    let _ = std::fs::remove_dir_all(&cassette_directory);
+
+    let recording = config()
+        .cassette(Cassette::Record(cassette_directory.clone()))
+        .build()
+        .blocking_http_client()
+        .unwrap();
+    let recorded_body = recording.get(&server.url).unwrap().text().unwrap();
+
+    let replaying = config()
+        .cassette(Cassette::Replay(cassette_directory.clone()))
+        .build()
+        .blocking_http_client()
+        .unwrap();
+    let replayed = replaying.get(&server.url).unwrap();
+    let replayed_content_type = replayed.headers()["content-type"].clone();
+    let replayed_body = replayed.text().unwrap();
+    std::fs::remove_dir_all(&cassette_directory).unwrap();
+
+    assert_eq!(recorded_body, "Firm,Employees");
+    assert_eq!(replayed_body, "Firm,Employees");
+    assert_eq!(replayed_content_type, "text/csv");
+    // Only the two recorded attempts reached the server
+    assert_eq!(server.request_times().len(), 2);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_client_replays_recorded_responses() {
+    let server = FlakyServer::start(&["200 OK\n\nFirm,Employees"]);
+    let cassette_directory = std::env::temp_dir().join("warn_scraper_cassette_async_replay");
+    let _ = std::fs::remove_dir_all(&cassette_directory);
+    config()
+        .cassette(Cassette::Record(cassette_directory.clone()))
+        .build()
+        .blocking_http_client()
+        .unwrap()
+        .get(&server.url)
+        .unwrap();
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let replayed_body = runtime.block_on(async {
+        let client = config()
+            .cassette(Cassette::Replay(cassette_directory.clone()))
+            .build()
+            .http_client()
+            .unwrap();
+        client.get(&server.url).await.unwrap().text().await.unwrap()
+    });
+    std::fs::remove_dir_all(&cassette_directory).unwrap();
+
+    assert_eq!(replayed_body, "Firm,Employees");
+    assert_eq!(server.request_times().len(), 1);
+}
+
+#[test]
+fn replaying_an_unrecorded_url_fails() {
+    let cassette_directory = std::env::temp_dir().join("warn_scraper_cassette_empty");
+    let client = config()
+        .cassette(Cassette::Replay(cassette_directory))
+        .build()
+        .blocking_http_client()
+        .unwrap();
+
+    let result = client.get("http://warn.invalid/never-recorded");
+
+    assert!(matches!(result, Err(ScraperError::Cassette(_))));
+}
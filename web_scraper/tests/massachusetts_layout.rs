@@ -0,0 +1,142 @@
+use web_scraper::{
+    error::ScraperError, layout_report::AnchorStatus, massachusetts_layout::MassachusettsLayout,
+    scraper::ScraperAdapter,
+};
+
+const PAGE_URL: &str =
+    "https://www.mass.gov/info-details/worker-adjustment-and-retraining-act-warn-weekly-report";
+const WEEKLY_REPORT: &str = include_str!("fixtures/ma/weekly_report.html");
+const RENAMED_HEADING: &str = include_str!("fixtures/ma/renamed_heading.html");
+const UNWRAPPED_NOTICES: &str = include_str!("fixtures/ma/unwrapped_notices.html");
+
+fn statuses(html: &str) -> Vec<(String, AnchorStatus, usize)> {
+    ScraperAdapter::check_layout(html, PAGE_URL, &MassachusettsLayout::default())
+        .checks
+        .into_iter()
+        .map(|check| (check.anchor, check.status, check.count))
+        .collect()
+}
+
+#[test]
+fn finds_every_anchor_of_the_expected_layout() {
+    let report =
+        ScraperAdapter::check_layout(WEEKLY_REPORT, PAGE_URL, &MassachusettsLayout::default());
+
+    assert!(report.is_healthy());
+    assert_eq!(
+        statuses(WEEKLY_REPORT),
+        vec![
+            ("WARN heading".to_string(), AnchorStatus::Found, 1),
+            ("notice section".to_string(), AnchorStatus::Found, 1),
+            ("notice paragraphs".to_string(), AnchorStatus::Found, 2),
+            ("year to date link".to_string(), AnchorStatus::Found, 1),
+        ]
+    );
+    assert!(report.checks[3].excerpts[0].contains("WARN Report for the week ending"));
+}
+
+#[test]
+fn shows_the_headings_found_when_the_warn_heading_is_missing() {
+    let report =
+        ScraperAdapter::check_layout(RENAMED_HEADING, PAGE_URL, &MassachusettsLayout::default());
+
+    assert!(!report.is_healthy());
+    assert_eq!(
+        statuses(RENAMED_HEADING),
+        vec![
+            ("WARN heading".to_string(), AnchorStatus::Missing, 0),
+            ("notice section".to_string(), AnchorStatus::Skipped, 0),
+            ("notice paragraphs".to_string(), AnchorStatus::Skipped, 0),
+            ("year to date link".to_string(), AnchorStatus::Skipped, 0),
+        ]
+    );
+    assert_eq!(
+        report.checks[0].excerpts,
+        vec![
+            "<h2>Related links</h2>".to_string(),
+            "<h2>WARN notices received this week</h2>".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn reports_notice_section_missing_its_wrappers() {
+    let report =
+        ScraperAdapter::check_layout(UNWRAPPED_NOTICES, PAGE_URL, &MassachusettsLayout::default());
+
+    assert_eq!(report.checks[1].status, AnchorStatus::Missing);
+    assert!(report.checks[1].excerpts[0].starts_with(r#"<ul class="ma__notices">"#));
+    assert_eq!(report.checks[2].status, AnchorStatus::Skipped);
+}
+
+#[test]
+fn reports_duplicate_warn_headings_as_ambiguous() {
+    let duplicated = WEEKLY_REPORT.replace(
+        "</main>",
+        "<section><h2>Companies that submitted WARN notices this past week</h2></section></main>",
+    );
+
+    let report =
+        ScraperAdapter::check_layout(&duplicated, PAGE_URL, &MassachusettsLayout::default());
+
+    assert_eq!(report.checks[0].status, AnchorStatus::Ambiguous);
+    assert_eq!(report.checks[0].count, 2);
+    assert_eq!(report.checks[1].status, AnchorStatus::Skipped);
+}
+
+#[test]
+fn layout_file_overrides_only_what_it_sets() {
+    let layout = MassachusettsLayout::parse(
+        r#"
+        version = 1
+        warn_heading = "WARN notices received this week"
+
+        [labels]
+        effective_date = "Layoff Date:"
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(layout.warn_heading, "WARN notices received this week");
+    assert_eq!(layout.labels.effective_date, "Layoff Date:");
+    assert_eq!(
+        layout.labels.company,
+        MassachusettsLayout::default().labels.company
+    );
+    assert_eq!(
+        layout.heading_selector,
+        MassachusettsLayout::default().heading_selector
+    );
+}
+
+#[test]
+fn renamed_heading_is_found_with_a_layout_file() {
+    let layout =
+        MassachusettsLayout::parse(r#"warn_heading = "WARN notices received this week""#).unwrap();
+
+    let report = ScraperAdapter::check_layout(RENAMED_HEADING, PAGE_URL, &layout);
+
+    assert_eq!(report.checks[0].status, AnchorStatus::Found);
+    assert_eq!(report.checks[2].status, AnchorStatus::Found);
+}
+
+#[test]
+fn rejects_invalid_layout_files() {
+    let invalid_layouts = [
+        "version = 2",
+        r#"heading_selector = "section >""#,
+        r#"warn_heading = "  ""#,
+        r#"effective_date = "Layoff Date:""#,
+    ];
+
+    for invalid_layout in invalid_layouts {
+        assert!(
+            matches!(
+                MassachusettsLayout::parse(invalid_layout),
+                Err(ScraperError::SourceConfig(_))
+            ),
+            "{} was accepted",
+            invalid_layout
+        );
+    }
+}
@@ -0,0 +1,147 @@
+use std::{fs, path::Path, time::Duration};
+
+use chrono::NaiveDate;
+use proto_generator::notice_id::NoticeId;
+use web_scraper::{
+    cassette::Cassette,
+    new_york_source::{
+        NewYorkListingEntry, NewYorkNoticeParser, NewYorkSource, NEW_YORK_BASE_URL,
+        NEW_YORK_LISTING_URL,
+    },
+    scraper_config::ScraperConfig,
+    warn_source::WarnSource,
+};
+
+const LISTING: &str = include_str!("fixtures/ny/listing.html");
+const CLOSURE_NOTICE: &str = include_str!("fixtures/ny/closure_notice.html");
+const LAYOFF_NOTICE: &str = include_str!("fixtures/ny/layoff_notice.html");
+const MISSING_COMPANY_NOTICE: &str = include_str!("fixtures/ny/missing_company_notice.html");
+
+#[test]
+fn parses_linked_rows_of_listing() {
+    let listing = NewYorkNoticeParser::parse_listing(LISTING, NEW_YORK_BASE_URL).unwrap();
+
+    assert_eq!(
+        listing,
+        vec![
+            NewYorkListingEntry {
+                url: "https://dol.ny.gov/warn-notices/2024/acme-widgets-inc".to_string(),
+                company: "Acme Widgets, Inc.".to_string(),
+                notice_date: NaiveDate::from_ymd_opt(2024, 3, 4),
+            },
+            NewYorkListingEntry {
+                url: "https://dol.ny.gov/warn-notices/2024/hudson-valley-bakery".to_string(),
+                company: "Hudson Valley Bakery LLC".to_string(),
+                notice_date: NaiveDate::from_ymd_opt(2024, 2, 27),
+            },
+            NewYorkListingEntry {
+                url: "https://dol.ny.gov/warn-notices/2024/empire-logistics".to_string(),
+                company: "Empire Logistics Corp.".to_string(),
+                notice_date: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn parses_closure_notice() {
+    let notice = NewYorkNoticeParser::parse_notice(CLOSURE_NOTICE).unwrap();
+
+    assert_eq!(notice.firm_name(), "Hudson Valley Bakery LLC");
+    assert_eq!(notice.firm_locations(), "45 River Road, Kingston, NY 12401");
+    assert_eq!(notice.affected_employees(), "87");
+    assert_eq!(notice.date_received(), "02/27/2024");
+    // Closures have no layoff date, so the closing date is used
+    assert_eq!(notice.effective_date(), "04/30/2024");
+    assert_eq!(notice.reason_for_dislocation(), "Economic");
+    assert_eq!(notice.state(), "NY");
+
+    let details = notice.new_york.as_ref().unwrap();
+    assert_eq!(details.event_number(), "2023-0912");
+    assert_eq!(details.reason_stated_for_filing(), "Plant Closing");
+    assert_eq!(details.county(), "Ulster");
+    assert_eq!(details.region(), "Mid-Hudson");
+    assert_eq!(details.business_type(), "Commercial Bakeries");
+    assert_eq!(details.total_employees(), "87");
+    assert_eq!(details.closing_date(), "04/30/2024");
+    assert_eq!(details.union_representation(), "None");
+    assert_eq!(details.classification(), "Plant Closing");
+}
+
+#[test]
+fn parses_layoff_notice() {
+    let notice = NewYorkNoticeParser::parse_notice(LAYOFF_NOTICE).unwrap();
+
+    assert_eq!(notice.firm_name(), "Acme Widgets, Inc.");
+    assert_eq!(
+        notice.firm_locations(),
+        "1200 Industrial Pkwy, Suite 4, Rochester, NY 14624"
+    );
+    assert_eq!(notice.affected_employees(), "150");
+    assert_eq!(notice.effective_date(), "05/03/2024");
+    assert_eq!(
+        notice.reason_for_dislocation(),
+        "Reorganization of production lines"
+    );
+
+    let details = notice.new_york.as_ref().unwrap();
+    assert_eq!(details.event_number(), "2023-1044");
+    assert_eq!(
+        details.reason_stated_for_filing(),
+        "Plant Closing; Mass Layoff"
+    );
+    assert_eq!(details.total_employees(), "410");
+    assert_eq!(details.closing_date(), "06/28/2024");
+    assert_eq!(details.union_representation(), "IAM Local 1234");
+}
+
+#[test]
+fn notice_without_company_is_an_error() {
+    assert!(NewYorkNoticeParser::parse_notice(MISSING_COMPANY_NOTICE).is_err());
+}
+
+/// Saves a page into the cassette directory as if it was recorded
+fn record_page(directory: &Path, url: &str, body: &str) {
+    let name = format!("{:016x}", NoticeId::fnv1a(url.as_bytes()));
+    fs::write(
+        directory.join(format!("{}.toml", name)),
+        format!("url = \"{}\"\nstatus = 200\n", url),
+    )
+    .unwrap();
+    fs::write(directory.join(format!("{}.body", name)), body).unwrap();
+}
+
+#[test]
+fn notices_that_fail_are_skipped() {
+    let directory = std::env::temp_dir().join("warn_scraper_ny_skipped_notices");
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    let notice_url = |path: &str| format!("{}/warn-notices/2024/{}", NEW_YORK_BASE_URL, path);
+    record_page(
+        &directory,
+        &format!("{}{}", NEW_YORK_BASE_URL, NEW_YORK_LISTING_URL),
+        LISTING,
+    );
+    record_page(
+        &directory,
+        &notice_url("hudson-valley-bakery"),
+        CLOSURE_NOTICE,
+    );
+    // Can't be parsed
+    record_page(
+        &directory,
+        &notice_url("acme-widgets-inc"),
+        MISSING_COMPANY_NOTICE,
+    );
+    // empire-logistics is never recorded, so fetching it fails
+
+    let config = ScraperConfig::builder()
+        .cassette(Cassette::Replay(directory.clone()))
+        .min_request_interval(Duration::ZERO)
+        .build();
+    let notices = NewYorkSource::new(config).unwrap().fetch_notices().unwrap();
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(notices.notices.len(), 1);
+    assert_eq!(notices.notices[0].firm_name(), "Hudson Valley Bakery LLC");
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use proto_generator::notices::WARNNotice;
+use web_scraper::{
+    massachusetts_layout::NoticeLabels, notice_paragraph_parser::NoticeParagraphParser,
+};
+
+fn parse(html: &str) -> Vec<(String, String, String, String)> {
+    NoticeParagraphParser::new(&NoticeLabels::default())
+        .parse_fragment(html)
+        .iter()
+        .map(fields)
+        .collect()
+}
+
+fn fields(notice: &WARNNotice) -> (String, String, String, String) {
+    (
+        notice.firm_name().to_string(),
+        notice.firm_locations().to_string(),
+        notice.affected_employees().to_string(),
+        notice.effective_date().to_string(),
+    )
+}
+
+fn expected_notices() -> Vec<(String, String, String, String)> {
+    [
+        ("Acme Widgets, Inc.", "Boston", "42", "04/01/2024"),
+        (
+            "Bay State Bakery",
+            "Worcester, Springfield",
+            "7",
+            "05/15/2024",
+        ),
+    ]
+    .iter()
+    .map(|(name, locations, employees, date)| {
+        (
+            name.to_string(),
+            locations.to_string(),
+            employees.to_string(),
+            date.to_string(),
+        )
+    })
+    .collect()
+}
+
+#[test]
+fn parses_spans_separated_by_line_breaks() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_spans.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn parses_bold_labels() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_bold_labels.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn parses_bold_labels_with_the_colon_outside() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_colon_outside_bold.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn ignores_the_case_and_spacing_of_labels() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_label_spelling.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn parses_a_paragraph_per_field() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_paragraph_per_field.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn parses_every_field_on_one_line() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_single_line.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn takes_the_next_line_as_the_value_of_a_label_ending_its_line() {
+    assert_eq!(
+        parse(include_str!("fixtures/ma/notices_value_on_next_line.html")),
+        expected_notices()
+    );
+}
+
+#[test]
+fn leaves_missing_and_empty_fields_empty() {
+    let html =
+        "<p>Company: Acme Widgets, Inc.<br>Company location(s):<br>Affected employees: 42</p>";
+
+    assert_eq!(
+        parse(html),
+        vec![(
+            "Acme Widgets, Inc.".to_string(),
+            String::new(),
+            "42".to_string(),
+            String::new(),
+        )]
+    );
+}
+
+#[test]
+fn does_not_match_labels_inside_words_or_values() {
+    let html = "<p>Company: Subcompany: Holdings<br>Company location(s): Boston</p>";
+
+    assert_eq!(
+        parse(html),
+        vec![(
+            "Subcompany: Holdings".to_string(),
+            "Boston".to_string(),
+            String::new(),
+            String::new(),
+        )]
+    );
+}
+
+#[test]
+fn uses_the_labels_given() {
+    let labels = NoticeLabels {
+        effective_date: "Layoff Date:".to_string(),
+        ..NoticeLabels::default()
+    };
+    let html = "<p>Company: Acme Widgets, Inc.<br>Layoff date: 04/01/2024</p>";
+
+    let notices = NoticeParagraphParser::new(&labels).parse_fragment(html);
+
+    assert_eq!(notices.len(), 1);
+    assert_eq!(notices[0].effective_date(), "04/01/2024");
+}
+
+#[test]
+fn parses_the_lines_only_some_notices_have() {
+    let notices = NoticeParagraphParser::new(&NoticeLabels::default())
+        .parse_fragment(include_str!("fixtures/ma/notices_extra_fields.html"));
+
+    assert_eq!(
+        notices.iter().map(fields).collect::<Vec<_>>(),
+        expected_notices()
+    );
+    assert_eq!(notices[0].notice_type(), "Closure");
+    assert_eq!(
+        notices[0].reason_for_dislocation(),
+        "Relocation of operations out of state"
+    );
+    assert_eq!(notices[0].union_representation(), "USW Local 12003");
+    assert_eq!(
+        notices[0].contact(),
+        "Jane Doe, HR Director, (617) 555-0100"
+    );
+    assert_eq!(notices[1].notice_type(), "Layoff");
+    assert_eq!(notices[1].union_representation(), "None");
+    assert!(!notices[1].has_reason_for_dislocation());
+    assert!(!notices[1].has_contact());
+}
+
+#[test]
+fn keeps_lines_with_other_labels_by_label() {
+    let notices = NoticeParagraphParser::new(&NoticeLabels::default())
+        .parse_fragment(include_str!("fixtures/ma/notices_extra_fields.html"));
+
+    assert_eq!(
+        notices[0].additional_fields,
+        HashMap::from([("Industry".to_string(), "Manufacturing".to_string())])
+    );
+    assert_eq!(
+        notices[1].additional_fields,
+        HashMap::from([
+            ("Notice received".to_string(), "03/01/2024".to_string()),
+            ("Shift".to_string(), "7:00 to 15:30".to_string()),
+        ])
+    );
+}
+
+#[test]
+fn does_not_take_unlabelled_text_with_colons_for_labels() {
+    let html = "<p>Company: Acme Widgets, Inc.<br>See https://www.mass.gov/warn for details<br>10:30 am: press release</p>";
+
+    let notices = NoticeParagraphParser::new(&NoticeLabels::default()).parse_fragment(html);
+
+    assert_eq!(notices.len(), 1);
+    assert!(notices[0].additional_fields.is_empty());
+}
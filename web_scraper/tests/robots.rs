@@ -0,0 +1,71 @@
+use reqwest::StatusCode;
+use web_scraper::robots::RobotsTxt;
+
+const USER_AGENT: &str = "WARN-Scraper/0.1.0 (+ops@example.com)";
+
+const ROBOTS_TXT: &str = "\
+# Comments are ignored
+User-agent: *
+Disallow: /private
+Allow: /private/warn-notices
+
+User-agent: badbot
+User-agent: WARN-Scraper
+Disallow: /search
+Allow: /search/warn$
+Disallow: /*.xlsx$
+";
+
+#[test]
+fn uses_the_group_naming_our_product_token() {
+    let robots_txt = RobotsTxt::parse(ROBOTS_TXT);
+
+    // Our group doesn't disallow /private, so the * group doesn't apply
+    assert!(robots_txt.is_allowed(USER_AGENT, "/private/report"));
+    assert!(!robots_txt.is_allowed(USER_AGENT, "/search?q=warn"));
+    assert!(!robots_txt.is_allowed("BadBot/2.0", "/search"));
+}
+
+#[test]
+fn falls_back_to_the_wildcard_group() {
+    let robots_txt = RobotsTxt::parse(ROBOTS_TXT);
+
+    assert!(!robots_txt.is_allowed("OtherBot/1.0", "/private/report"));
+    assert!(robots_txt.is_allowed("OtherBot/1.0", "/private/warn-notices/2024"));
+    assert!(robots_txt.is_allowed("OtherBot/1.0", "/search"));
+}
+
+#[test]
+fn most_specific_rule_wins() {
+    let robots_txt = RobotsTxt::parse(ROBOTS_TXT);
+
+    assert!(robots_txt.is_allowed(USER_AGENT, "/search/warn"));
+    assert!(!robots_txt.is_allowed(USER_AGENT, "/search/warn/2024"));
+}
+
+#[test]
+fn matches_wildcards_and_end_anchors() {
+    let robots_txt = RobotsTxt::parse(ROBOTS_TXT);
+
+    assert!(!robots_txt.is_allowed(USER_AGENT, "/reports/warn_2024.xlsx"));
+    assert!(robots_txt.is_allowed(USER_AGENT, "/reports/warn_2024.xlsx?download=1"));
+}
+
+#[test]
+fn robots_txt_itself_is_always_allowed() {
+    assert!(RobotsTxt::disallow_all().is_allowed(USER_AGENT, "/robots.txt"));
+    assert!(!RobotsTxt::disallow_all().is_allowed(USER_AGENT, "/"));
+}
+
+#[test]
+fn unavailable_robots_txt() {
+    assert_eq!(
+        RobotsTxt::unavailable(StatusCode::NOT_FOUND),
+        Some(RobotsTxt::allow_all())
+    );
+    assert_eq!(
+        RobotsTxt::unavailable(StatusCode::SERVICE_UNAVAILABLE),
+        Some(RobotsTxt::disallow_all())
+    );
+    assert_eq!(RobotsTxt::unavailable(StatusCode::OK), None);
+}
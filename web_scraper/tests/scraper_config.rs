@@ -0,0 +1,127 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use web_scraper::{
+    cassette::Cassette,
+    massachusetts_layout::MassachusettsLayout,
+    scraper_config::{
+        ScraperConfig, DEFAULT_DOWNLOAD_DIRECTORY, DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+        DEFAULT_MAX_RETRIES, DEFAULT_MAX_RETRY_DELAY, DEFAULT_MIN_REQUEST_INTERVAL,
+        DEFAULT_RETRY_DELAY, DEFAULT_TIMEOUT, DEFAULT_USER_AGENT,
+    },
+};
+
+#[test]
+fn builder_starts_from_the_defaults() {
+    let config = ScraperConfig::builder().build();
+
+    assert_eq!(config.timeout, DEFAULT_TIMEOUT);
+    assert_eq!(config.user_agent, DEFAULT_USER_AGENT);
+    assert_eq!(config.contact, None);
+    assert!(config.respect_robots_txt);
+    assert_eq!(config.proxy, None);
+    assert!(config.no_proxy.is_empty());
+    assert!(config.root_certificates.is_empty());
+    assert_eq!(
+        config.download_directory,
+        PathBuf::from(DEFAULT_DOWNLOAD_DIRECTORY)
+    );
+    assert!(config.include_year_to_date);
+    assert_eq!(config.max_retries, DEFAULT_MAX_RETRIES);
+    assert_eq!(config.retry_delay, DEFAULT_RETRY_DELAY);
+    assert_eq!(config.max_retry_delay, DEFAULT_MAX_RETRY_DELAY);
+    assert_eq!(config.min_request_interval, DEFAULT_MIN_REQUEST_INTERVAL);
+    assert_eq!(
+        config.max_concurrent_downloads,
+        DEFAULT_MAX_CONCURRENT_DOWNLOADS
+    );
+    assert!(config.archive_workbook_urls.is_empty());
+    assert_eq!(config.massachusetts_layout, MassachusettsLayout::default());
+    assert_eq!(config.cassette, None);
+}
+
+#[test]
+fn builder_overrides_only_what_is_set() {
+    let config = ScraperConfig::builder()
+        .timeout(Duration::from_secs(5))
+        .user_agent("TestAgent/1.0")
+        .contact("ops@example.com")
+        .respect_robots_txt(false)
+        .proxy("http://proxy.corp:3128")
+        .no_proxy(vec!["mass.gov".to_string()])
+        .download_directory("/tmp/warn_test")
+        .include_year_to_date(false)
+        .max_retries(5)
+        .min_request_interval(Duration::ZERO)
+        .archive_workbook_urls(vec!["https://example.com/2022.xlsx".to_string()])
+        .cassette(Cassette::Replay(PathBuf::from("/tmp/cassette")))
+        .build();
+
+    assert_eq!(config.timeout, Duration::from_secs(5));
+    assert_eq!(config.user_agent, "TestAgent/1.0");
+    assert_eq!(config.contact.as_deref(), Some("ops@example.com"));
+    assert!(!config.respect_robots_txt);
+    assert_eq!(config.proxy.as_deref(), Some("http://proxy.corp:3128"));
+    assert_eq!(config.no_proxy, vec!["mass.gov"]);
+    assert_eq!(config.download_directory, PathBuf::from("/tmp/warn_test"));
+    assert!(!config.include_year_to_date);
+    assert_eq!(config.max_retries, 5);
+    assert_eq!(config.min_request_interval, Duration::ZERO);
+    assert_eq!(
+        config.archive_workbook_urls,
+        vec!["https://example.com/2022.xlsx"]
+    );
+    assert_eq!(
+        config.cassette,
+        Some(Cassette::Replay(PathBuf::from("/tmp/cassette")))
+    );
+    // Left alone
+    assert_eq!(config.retry_delay, DEFAULT_RETRY_DELAY);
+    assert_eq!(config.max_retry_delay, DEFAULT_MAX_RETRY_DELAY);
+    assert_eq!(
+        config.max_concurrent_downloads,
+        DEFAULT_MAX_CONCURRENT_DOWNLOADS
+    );
+}
+
+#[test]
+fn user_agent_header_includes_the_contact() {
+    let builder = || ScraperConfig::builder().user_agent("TestAgent/1.0");
+
+    assert_eq!(builder().build().user_agent_header(), "TestAgent/1.0");
+    assert_eq!(
+        builder()
+            .contact("ops@example.com")
+            .build()
+            .user_agent_header(),
+        "TestAgent/1.0 (+ops@example.com)"
+    );
+}
+
+#[test]
+fn loads_the_massachusetts_layout_from_toml() {
+    let example = MassachusettsLayout::load(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../source_configs/massachusetts_layout.toml"
+    )))
+    .unwrap();
+    let renamed =
+        MassachusettsLayout::parse("warn_heading = \"WARN notices this week\"\n").unwrap();
+
+    let config = ScraperConfig::builder()
+        .massachusetts_layout(renamed.clone())
+        .build();
+
+    assert_eq!(example, MassachusettsLayout::default());
+    assert_eq!(config.massachusetts_layout, renamed);
+    assert_eq!(
+        config.massachusetts_layout.warn_heading,
+        "WARN notices this week"
+    );
+    assert_eq!(
+        config.massachusetts_layout.labels,
+        MassachusettsLayout::default().labels
+    );
+}
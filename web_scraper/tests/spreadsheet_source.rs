@@ -0,0 +1,166 @@
+use std::path::Path;
+
+use web_scraper::{
+    error::ScraperError,
+    spreadsheet_source::{SpreadsheetFormat, SpreadsheetParser, SpreadsheetSourceConfig},
+};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/spreadsheet");
+const SOURCE_CONFIG: &str = include_str!("fixtures/spreadsheet/source.toml");
+
+fn fixture(file_name: &str) -> std::path::PathBuf {
+    Path::new(FIXTURES).join(file_name)
+}
+
+fn config_error(config_text: &str) -> String {
+    match SpreadsheetSourceConfig::parse(config_text) {
+        Err(ScraperError::SourceConfig(message)) => message,
+        result => panic!(
+            "Expected a source config error, got {:?}",
+            result.map(|_| ())
+        ),
+    }
+}
+
+#[test]
+fn loads_a_config_file() {
+    let config = SpreadsheetSourceConfig::load(&fixture("source.toml")).unwrap();
+
+    assert_eq!(config.id, "ex");
+    assert_eq!(config.state, "EX");
+    assert_eq!(config.header_row, 2);
+    assert_eq!(config.format(), SpreadsheetFormat::Csv);
+    assert_eq!(config.sheet, None);
+    assert!(config.archive_urls.is_empty());
+    assert_eq!(config.columns.firm_name.as_deref(), Some("Company Name"));
+    assert_eq!(config.columns.reason_for_dislocation, None);
+}
+
+#[test]
+fn the_example_config_is_valid() {
+    let config = SpreadsheetSourceConfig::load(Path::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../source_configs/example_spreadsheet_source.toml"
+    )))
+    .unwrap();
+
+    assert_eq!(config.format(), SpreadsheetFormat::Xlsx);
+    assert_eq!(config.sheet.as_deref(), Some("WARN Notices"));
+}
+
+#[test]
+fn format_defaults_to_the_extension_of_the_url() {
+    let config_for = |url: &str| {
+        SpreadsheetSourceConfig::parse(
+            &SOURCE_CONFIG.replace("http://127.0.0.1/warn/notices.csv", url),
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+        config_for("https://example.gov/NOTICES.CSV").format(),
+        SpreadsheetFormat::Csv
+    );
+    assert_eq!(
+        config_for("https://example.gov/notices.xlsx").format(),
+        SpreadsheetFormat::Xlsx
+    );
+    assert_eq!(
+        config_for("https://example.gov/notices").format(),
+        SpreadsheetFormat::Xlsx
+    );
+}
+
+#[test]
+fn rejects_invalid_configs() {
+    assert_eq!(
+        config_error(&SOURCE_CONFIG.replace("header_row = 2", "header_row = 0")),
+        "header_row counts from 1"
+    );
+    assert_eq!(
+        config_error(&SOURCE_CONFIG.replace("firm_name = \"Company Name\"\n", "")),
+        "columns.firm_name is required"
+    );
+    assert!(
+        config_error(&SOURCE_CONFIG.replace("firm_name =", "company_name ="))
+            .contains("company_name")
+    );
+    assert!(config_error(&SOURCE_CONFIG.replace("id = \"ex\"\n", "")).contains("id"));
+}
+
+#[test]
+fn load_names_the_file_in_errors() {
+    let config_path = std::env::temp_dir().join("warn_scraper_bad_source.toml");
+    std::fs::write(&config_path, "id = \"ex\"").unwrap();
+
+    let result = SpreadsheetSourceConfig::load(&config_path);
+    std::fs::remove_file(&config_path).unwrap();
+
+    assert!(matches!(
+        result,
+        Err(ScraperError::SourceConfig(message)) if message.starts_with(&config_path.display().to_string())
+    ));
+}
+
+#[test]
+fn maps_columns_onto_notice_fields() {
+    let config = SpreadsheetSourceConfig::parse(SOURCE_CONFIG).unwrap();
+
+    let notices = SpreadsheetParser::new(&config)
+        .parse_file(&fixture("notices.csv"))
+        .unwrap();
+
+    assert_eq!(notices.notices.len(), 2);
+    let acme = &notices.notices[0];
+    assert_eq!(acme.firm_name(), "Acme Widgets");
+    assert_eq!(acme.firm_locations(), "Worcester");
+    assert_eq!(acme.affected_employees(), "40");
+    assert_eq!(acme.notice_type(), "Closure");
+    assert_eq!(acme.state(), "EX");
+    assert!(!acme.has_reason_for_dislocation());
+    let bakery = &notices.notices[1];
+    assert_eq!(bakery.firm_name(), "Bay State Bakery");
+    assert!(!bakery.has_notice_type());
+}
+
+#[test]
+fn converts_dates_in_the_configured_formats() {
+    let config = SpreadsheetSourceConfig::parse(SOURCE_CONFIG).unwrap();
+
+    let notices = SpreadsheetParser::new(&config)
+        .parse_file(&fixture("notices.csv"))
+        .unwrap();
+
+    assert_eq!(notices.notices[0].effective_date(), "2023-11-01");
+    // Two digit years aren't read as the first century by %Y
+    assert_eq!(notices.notices[0].date_received(), "2023-09-01");
+    assert_eq!(notices.notices[1].date_received(), "2023-09-05");
+    // Dates in no configured format are kept as written
+    assert_eq!(notices.notices[1].effective_date(), "TBD");
+}
+
+#[test]
+fn a_column_missing_from_the_spreadsheet_is_an_error() {
+    let config = SpreadsheetSourceConfig::parse(
+        &SOURCE_CONFIG.replace("\"Layoff Date\"", "\"Separation Date\""),
+    )
+    .unwrap();
+
+    let result = SpreadsheetParser::new(&config).parse_file(&fixture("notices.csv"));
+
+    assert!(matches!(
+        result,
+        Err(ScraperError::ExcelParsing(message)) if message.contains("Separation Date")
+    ));
+}
+
+#[test]
+fn a_missing_heading_row_is_an_error() {
+    let config =
+        SpreadsheetSourceConfig::parse(&SOURCE_CONFIG.replace("header_row = 2", "header_row = 9"))
+            .unwrap();
+
+    let result = SpreadsheetParser::new(&config).parse_file(&fixture("notices.csv"));
+
+    assert!(matches!(result, Err(ScraperError::ExcelParsing(_))));
+}
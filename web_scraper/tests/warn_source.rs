@@ -0,0 +1,205 @@
+use proto_generator::notices::{NoticeKind, WARNNotice, WARNNotices};
+use web_scraper::{
+    error::{ScraperError, ScraperResult},
+    scraper_config::ScraperConfig,
+    warn_source::{SourceRegistry, WarnSource},
+};
+
+/// Serves fixed notices, tagged with its state like a real source
+struct StubSource {
+    id: String,
+    state: String,
+    firm_names: Vec<&'static str>,
+    weekly_firm_names: Vec<&'static str>,
+}
+
+impl StubSource {
+    fn notices(&self, firm_names: &[&str]) -> WARNNotices {
+        let mut notices = WARNNotices::new();
+        notices.notices = firm_names
+            .iter()
+            .map(|firm_name| {
+                let mut notice = WARNNotice::new();
+                notice.set_firm_name(firm_name.to_string());
+                notice.set_notice_type("Closure".to_string());
+                notice
+            })
+            .collect();
+        self.tag_with_state(&mut notices);
+        notices
+    }
+}
+
+impl WarnSource for StubSource {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn state(&self) -> &str {
+        &self.state
+    }
+
+    fn fetch_notices(&self) -> ScraperResult<WARNNotices> {
+        Ok(self.notices(&self.firm_names))
+    }
+
+    fn fetch_weekly_notices(&self) -> ScraperResult<WARNNotices> {
+        Ok(self.notices(&self.weekly_firm_names))
+    }
+}
+
+/// Fails every fetch
+struct FailingSource {}
+
+impl WarnSource for FailingSource {
+    fn id(&self) -> &str {
+        "fail"
+    }
+
+    fn state(&self) -> &str {
+        "FL"
+    }
+
+    fn fetch_notices(&self) -> ScraperResult<WARNNotices> {
+        Err(ScraperError::Parsing("unexpected page".to_string()))
+    }
+
+    fn fetch_weekly_notices(&self) -> ScraperResult<WARNNotices> {
+        self.fetch_notices()
+    }
+}
+
+fn stub(
+    id: &str,
+    state: &str,
+    firm_names: Vec<&'static str>,
+    weekly_firm_names: Vec<&'static str>,
+) -> Box<dyn WarnSource> {
+    Box::new(StubSource {
+        id: id.to_string(),
+        state: state.to_string(),
+        firm_names,
+        weekly_firm_names,
+    })
+}
+
+fn registry() -> SourceRegistry {
+    let mut registry = SourceRegistry::empty();
+    registry.register("aa", "Stub A", |_| {
+        Ok(stub("aa", "AA", vec!["Acme Widgets"], vec![]))
+    });
+    registry.register("bb", "Stub B", |_| {
+        Ok(stub("bb", "BB", vec!["Bay State Bakery"], vec![]))
+    });
+    registry
+}
+
+fn firm_names_and_states(notices: &WARNNotices) -> Vec<(&str, &str)> {
+    notices
+        .notices
+        .iter()
+        .map(|notice| (notice.firm_name(), notice.state()))
+        .collect()
+}
+
+#[test]
+fn lists_sources_by_id() {
+    let mut registry = registry();
+    registry.register("AB", "Stub AB", |_| Ok(stub("ab", "AB", vec![], vec![])));
+
+    assert_eq!(
+        registry.sources(),
+        vec![
+            ("aa".to_string(), "Stub A".to_string()),
+            ("ab".to_string(), "Stub AB".to_string()),
+            ("bb".to_string(), "Stub B".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn creates_sources_by_id_ignoring_case() {
+    let source = registry().create("BB", &ScraperConfig::default()).unwrap();
+
+    assert_eq!(source.id(), "bb");
+    assert_eq!(source.state(), "BB");
+}
+
+#[test]
+fn registering_an_id_again_replaces_the_source() {
+    let mut registry = registry();
+    registry.register("aa", "Replacement", |_| {
+        Ok(stub("aa", "ZZ", vec![], vec![]))
+    });
+
+    assert_eq!(registry.sources()[0].1, "Replacement");
+    let source = registry.create("aa", &ScraperConfig::default()).unwrap();
+    assert_eq!(source.state(), "ZZ");
+}
+
+#[test]
+fn unknown_sources_are_an_error_listing_the_known_ones() {
+    let config = ScraperConfig::default();
+
+    match registry().create("zz", &config) {
+        Err(ScraperError::UnknownSource(message)) => {
+            assert_eq!(message, "zz. Known sources are: aa, bb")
+        }
+        result => panic!("Expected an unknown source, got {:?}", result.map(|_| ())),
+    }
+    assert!(registry()
+        .create_all(&["aa".to_string(), "zz".to_string()], &config)
+        .is_err());
+}
+
+#[test]
+fn merges_the_notices_of_every_source_in_order() {
+    let sources = vec![
+        stub("aa", "AA", vec!["Acme Widgets", "Cape Cod Crafts"], vec![]),
+        stub("bb", "BB", vec!["Bay State Bakery"], vec!["Dedham Dairy"]),
+    ];
+
+    let notices = SourceRegistry::fetch_notices(&sources, false).unwrap();
+
+    assert_eq!(
+        firm_names_and_states(&notices),
+        vec![
+            ("Acme Widgets", "AA"),
+            ("Cape Cod Crafts", "AA"),
+            ("Bay State Bakery", "BB"),
+        ]
+    );
+    // Unclassified notices are classified once merged
+    assert!(notices
+        .notices
+        .iter()
+        .all(|notice| notice.classification.kind() == NoticeKind::KIND_CLOSURE));
+}
+
+#[test]
+fn merges_only_weekly_notices_when_asked() {
+    let sources = vec![
+        stub("aa", "AA", vec!["Acme Widgets"], vec![]),
+        stub("bb", "BB", vec!["Bay State Bakery"], vec!["Dedham Dairy"]),
+    ];
+
+    let notices = SourceRegistry::fetch_notices(&sources, true).unwrap();
+
+    assert_eq!(
+        firm_names_and_states(&notices),
+        vec![("Dedham Dairy", "BB")]
+    );
+}
+
+#[test]
+fn a_failing_source_fails_the_fetch() {
+    let sources = vec![
+        stub("aa", "AA", vec!["Acme Widgets"], vec![]),
+        Box::new(FailingSource {}) as Box<dyn WarnSource>,
+    ];
+
+    assert!(matches!(
+        SourceRegistry::fetch_notices(&sources, false),
+        Err(ScraperError::Parsing(_))
+    ));
+}
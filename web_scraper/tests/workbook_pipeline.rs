@@ -0,0 +1,251 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use web_scraper::{
+    scraper::ScraperAdapter,
+    scraper_config::ScraperConfig,
+    spreadsheet_source::{SpreadsheetSource, SpreadsheetSourceConfig},
+    warn_source::WarnSource,
+};
+
+const WEEKLY_REPORT: &str = include_str!("fixtures/ma/weekly_report.html");
+const YEAR_TO_DATE_WORKBOOK: &[u8] = include_bytes!("fixtures/ma/year_to_date.xlsx");
+const ARCHIVE_WORKBOOK: &[u8] = include_bytes!("fixtures/ma/archive_2023.xlsx");
+
+/// Local server answering each request on its own thread, after the delay
+/// given for the requested path. Keeps track of how many requests it was
+/// answering at once.
+struct SlowServer {
+    base_url: String,
+    max_in_flight: Arc<AtomicUsize>,
+    finished: Arc<Mutex<Vec<String>>>,
+}
+
+impl SlowServer {
+    /// # Params
+    /// * workbooks - Path, delay before answering and firm name of the only
+    ///   notice of each csv workbook served
+    fn start(workbooks: &[(&str, u64, &str)]) -> SlowServer {
+        Self::serve(
+            workbooks
+                .iter()
+                .map(|(path, delay, firm_name)| {
+                    (
+                        path.to_string(),
+                        (*delay, format!("Company\n{}\n", firm_name).into_bytes()),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// # Params
+    /// * files - Delay before answering and body of each path served
+    fn serve(files: HashMap<String, (u64, Vec<u8>)>) -> SlowServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let workbooks = Arc::new(files);
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let finished = Arc::new(Mutex::new(Vec::new()));
+
+        let server = SlowServer {
+            base_url,
+            max_in_flight: max_in_flight.clone(),
+            finished: finished.clone(),
+        };
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let workbooks = workbooks.clone();
+                let in_flight = in_flight.clone();
+                let max_in_flight = max_in_flight.clone();
+                let finished = finished.clone();
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                        line.clear();
+                    }
+                    let path = request_line
+                        .split_whitespace()
+                        .nth(1)
+                        .unwrap_or_default()
+                        .to_string();
+
+                    let answering = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max_in_flight.fetch_max(answering, Ordering::SeqCst);
+                    let (status, body) = match workbooks.get(&path) {
+                        Some((delay, body)) => {
+                            thread::sleep(Duration::from_millis(*delay));
+                            ("200 OK", body.as_slice())
+                        }
+                        None => ("404 Not Found", &[][..]),
+                    };
+                    finished.lock().unwrap().push(path);
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    write!(
+                        stream,
+                        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        status,
+                        body.len()
+                    )
+                    .unwrap();
+                    stream.write_all(body).unwrap();
+                });
+            }
+        });
+
+        server
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+
+    fn finished(&self) -> Vec<String> {
+        self.finished.lock().unwrap().clone()
+    }
+}
+
+/// Firm names of the notices of a csv source made of the given workbooks, in
+/// the order they were merged
+fn fetch_firm_names(
+    server: &SlowServer,
+    id: &str,
+    paths: &[&str],
+    max_concurrent_downloads: usize,
+) -> Vec<String> {
+    let source_config = SpreadsheetSourceConfig::parse(&format!(
+        "id = \"{}\"\nname = \"Test\"\nstate = \"EX\"\nformat = \"csv\"\n\
+         url = \"{}\"\narchive_urls = [{}]\n\n[columns]\nfirm_name = \"Company\"\n",
+        id,
+        server.url(paths[0]),
+        paths[1..]
+            .iter()
+            .map(|path| format!("\"{}\"", server.url(path)))
+            .collect::<Vec<String>>()
+            .join(", ")
+    ))
+    .unwrap();
+    let download_directory = std::env::temp_dir().join(format!("warn_scraper_pipeline_{}", id));
+    let scraper_config = ScraperConfig::builder()
+        .timeout(Duration::from_secs(5))
+        .min_request_interval(Duration::ZERO)
+        .respect_robots_txt(false)
+        .max_concurrent_downloads(max_concurrent_downloads)
+        .download_directory(&download_directory)
+        .build();
+
+    let source = SpreadsheetSource::new(source_config, scraper_config).unwrap();
+    let _ = std::fs::remove_dir_all(&download_directory);
+
+    source
+        .fetch_notices()
+        .unwrap()
+        .notices
+        .iter()
+        .map(|notice| notice.firm_name().to_string())
+        .collect()
+}
+
+#[test]
+fn merges_workbooks_in_the_order_given_however_downloads_finish() {
+    let server = SlowServer::start(&[
+        ("/current.csv", 800, "Acme Widgets"),
+        ("/2023.csv", 200, "Bay State Bakery"),
+        ("/2022.csv", 400, "Cape Cod Crafts"),
+    ]);
+
+    let firm_names = fetch_firm_names(
+        &server,
+        "ordered",
+        &["/current.csv", "/2023.csv", "/2022.csv"],
+        3,
+    );
+
+    // The downloads overlapped and finished out of order
+    assert_eq!(
+        server.finished(),
+        vec!["/2023.csv", "/2022.csv", "/current.csv"]
+    );
+    assert_eq!(
+        firm_names,
+        vec!["Acme Widgets", "Bay State Bakery", "Cape Cod Crafts"]
+    );
+}
+
+#[test]
+fn downloads_at_most_the_configured_number_of_workbooks_at_once() {
+    let workbooks = [
+        ("/2024.csv", 200, "Acme Widgets"),
+        ("/2023.csv", 200, "Bay State Bakery"),
+        ("/2022.csv", 200, "Cape Cod Crafts"),
+        ("/2021.csv", 200, "Dedham Dairy"),
+        ("/2020.csv", 200, "Essex Electric"),
+    ];
+    let server = SlowServer::start(&workbooks);
+
+    let firm_names = fetch_firm_names(&server, "bounded", &workbooks.map(|(path, _, _)| path), 2);
+
+    assert_eq!(server.max_in_flight(), 2);
+    assert_eq!(
+        firm_names,
+        workbooks
+            .map(|(_, _, firm_name)| firm_name.to_string())
+            .to_vec()
+    );
+}
+
+#[test]
+fn parses_massachusetts_archive_workbooks_as_xlsx() {
+    let server = SlowServer::serve(HashMap::from([
+        (
+            "/weekly".to_string(),
+            (0, WEEKLY_REPORT.as_bytes().to_vec()),
+        ),
+        (
+            "/doc/warn-report-for-the-week-ending-march-1-2024/download".to_string(),
+            (0, YEAR_TO_DATE_WORKBOOK.to_vec()),
+        ),
+        ("/archive/2023".to_string(), (0, ARCHIVE_WORKBOOK.to_vec())),
+    ]));
+    let download_directory = std::env::temp_dir().join("warn_scraper_pipeline_archive");
+    let _ = std::fs::remove_dir_all(&download_directory);
+    let config = ScraperConfig::builder()
+        .timeout(Duration::from_secs(5))
+        .min_request_interval(Duration::ZERO)
+        .respect_robots_txt(false)
+        .archive_workbook_urls(vec![server.url("/archive/2023")])
+        .download_directory(&download_directory)
+        .build();
+
+    let notices = ScraperAdapter::with_config(server.url("/"), "weekly", config)
+        .unwrap()
+        .get_notices()
+        .unwrap();
+
+    let firm_names: Vec<&str> = notices
+        .notices
+        .iter()
+        .map(|notice| notice.firm_name())
+        .collect();
+    assert!(firm_names.contains(&"Bay State Bakery"), "{:?}", firm_names);
+    assert!(firm_names.contains(&"Dedham Dairy"), "{:?}", firm_names);
+}